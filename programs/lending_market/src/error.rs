@@ -5,7 +5,7 @@ pub enum ErrorCode {
     #[msg("Admin only")]
     Unauthorized,
 
-    #[msg("Basis points must be <= 10_000")]
+    #[msg("Fee exceeds maximum allowed")]
     FeeTooHigh,
 
     #[msg("Invalid fee recipient")]
@@ -58,4 +58,88 @@ pub enum ErrorCode {
 
     #[msg("Offer already taken")]
     OfferAlreadyTaken,
+
+    #[msg("Invalid price feed")]
+    InvalidPriceFeed,
+
+    #[msg("Oracle price is stale")]
+    StalePrice,
+
+    #[msg("Oracle price must be positive")]
+    InvalidPrice,
+
+    #[msg("Price calculation overflow")]
+    PriceCalculationOverflow,
+
+    #[msg("Invalid admin")]
+    InvalidAdmin,
+
+    #[msg("Asset pair market is already active")]
+    MarketAlreadyActive,
+
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+
+    #[msg("Borrow amount below the offer's minimum fill")]
+    FillBelowMinimum,
+
+    #[msg("Liquidation penalty exceeds maximum allowed")]
+    LiquidationPenaltyTooHigh,
+
+    #[msg("Liquidation bonus exceeds maximum allowed")]
+    LiquidationBonusTooHigh,
+
+    #[msg("Auction price exceeds the bid's maximum price")]
+    BidAboveMaxPrice,
+
+    #[msg("Auction has not reached its floor price yet")]
+    AuctionNotEnded,
+
+    #[msg("Fixed-point math overflow")]
+    MathOverflow,
+
+    #[msg("Invalid rate model")]
+    InvalidRateModel,
+
+    #[msg("Asset pair market has no variable rate model")]
+    VariableRateNotEnabled,
+
+    #[msg("Repayment already requested")]
+    RepaymentAlreadyRequested,
+
+    #[msg("New repayment deadline must be later than the current one")]
+    InvalidRepaymentDeadline,
+
+    #[msg("Repayment notice duration outside the allowed range")]
+    InvalidNoticeDuration,
+
+    #[msg("Invalid loan duration")]
+    InvalidLoanDuration,
+
+    #[msg("Fixed-term loan cannot be called before maturity")]
+    LoanNotMatured,
+
+    #[msg("Interest rate above the borrow request's maximum")]
+    InterestRateAboveMax,
+
+    #[msg("Remaining accounts must be lending offer, escrow, loan and collateral vault groups")]
+    InvalidRemainingAccounts,
+
+    #[msg("Borrow amount could not be filled within the limits")]
+    OrderNotFilled,
+
+    #[msg("Offer expiration must be in the future")]
+    InvalidOfferExpiration,
+
+    #[msg("Offer has expired")]
+    OfferExpired,
+
+    #[msg("Offer has not expired")]
+    OfferNotExpired,
+
+    #[msg("Price confidence interval is too wide")]
+    PriceConfidenceTooWide,
+
+    #[msg("Variable-rate offers are not indexed on the orderbook")]
+    VariableOfferNotIndexed,
 }
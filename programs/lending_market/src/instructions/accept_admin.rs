@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct AdminTransferred {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LendingMarket::SEED],
+        bump = lending_market.bump,
+        constraint = lending_market.pending_admin == Some(pending_admin.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+}
+
+/// Second step of the admin transfer, signed by the proposed key
+pub fn accept_admin_handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let lending_market = &mut ctx.accounts.lending_market;
+    let old_admin = lending_market.admin;
+
+    lending_market.admin = ctx.accounts.pending_admin.key();
+    lending_market.pending_admin = None;
+
+    emit!(AdminTransferred {
+        old_admin,
+        new_admin: lending_market.admin,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer, CloseAccount};
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::instructions::start_collateral_auction::CollateralAuction;
+use crate::state::Loan;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct CollateralAuctionFilled {
+    pub auction: Pubkey,
+    pub loan: Pubkey,
+    pub bidder: Pubkey,
+    pub price: u64,
+    pub lender_received: u64,
+    pub lender_fee: u64,
+    pub borrower_received: u64,
+}
+
+#[derive(Accounts)]
+pub struct BidCollateralAuction<'info> {
+    pub bidder: Signer<'info>,
+
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Box<Account<'info, LendingMarket>>,
+
+    #[account(
+        mut,
+        close = lender,
+        has_one = loan,
+        has_one = lender,
+        has_one = borrower,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        seeds = [CollateralAuction::SEED, loan.key().as_ref()],
+        bump = collateral_auction.bump,
+    )]
+    pub collateral_auction: Box<Account<'info, CollateralAuction>>,
+
+    #[account(
+        mut,
+        close = lender,
+    )]
+    pub loan: Box<Account<'info, Loan>>,
+
+    pub asset_pair_market: Box<Account<'info, AssetPairMarket>>,
+
+    #[account(
+        mut,
+        seeds = [Loan::COLLATERAL_SEED,loan.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = bidder_loan_token_account.owner == bidder.key(),
+    )]
+    pub bidder_loan_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = bidder_collateral_token_account.owner == bidder.key(),
+        constraint = bidder_collateral_token_account.mint == collateral_vault.mint,
+    )]
+    pub bidder_collateral_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated as lender from auction, receives the auction, loan and vault rent
+    #[account(mut)]
+    pub lender: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = lender_token_account.owner == lender.key(),
+        constraint = lender_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub lender_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated as borrower from auction
+    pub borrower: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = borrower_loan_token_account.owner == borrower.key(),
+        constraint = borrower_loan_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub borrower_loan_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated in lending_market
+    #[account(
+        constraint = fee_recipient.key() == lending_market.fee_recipient @ ErrorCode::InvalidFeeRecipient,
+    )]
+    pub fee_recipient: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = fee_recipient_token_account.owner == fee_recipient.key(),
+        constraint = fee_recipient_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub fee_recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Buy the whole lot at the current auction price, `max_price` protects against front-running.
+/// Proceeds repay the lender up to the debt, anything above goes to the borrower.
+/// The lender fee is charged on the lender's share, as on every other exit path.
+pub fn bid_collateral_auction_handler(ctx: Context<BidCollateralAuction>, max_price: u64) -> Result<()> {
+    let collateral_auction = &ctx.accounts.collateral_auction;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let price = collateral_auction.current_price(current_time);
+    require!(price <= max_price, ErrorCode::BidAboveMaxPrice);
+
+    let lender_received = price.min(collateral_auction.debt);
+    let borrower_received = price - lender_received;
+
+    // Calculate lender fee (2%)
+    let lender_fee = lender_received
+        .checked_mul(ctx.accounts.lending_market.lender_fee_bps)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?
+        .checked_div(10000)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+
+    let lender_receives = lender_received
+        .checked_sub(lender_fee)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+
+    // 1. Transfer proceeds (minus fee) from bidder to lender
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.bidder_loan_token_account.to_account_info(),
+        to: ctx.accounts.lender_token_account.to_account_info(),
+        authority: ctx.accounts.bidder.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, lender_receives)?;
+
+    // 2. Transfer fee from bidder to fee recipient
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.bidder_loan_token_account.to_account_info(),
+        to: ctx.accounts.fee_recipient_token_account.to_account_info(),
+        authority: ctx.accounts.bidder.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, lender_fee)?;
+
+    // 3. Transfer any surplus from bidder to borrower
+    if borrower_received > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.bidder_loan_token_account.to_account_info(),
+            to: ctx.accounts.borrower_loan_token_account.to_account_info(),
+            authority: ctx.accounts.bidder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, borrower_received)?;
+    }
+
+    let loan_key = ctx.accounts.loan.key();
+
+    // Create collateral vault authority seeds
+    let collateral_seeds = &[
+        Loan::COLLATERAL_SEED,
+        loan_key.as_ref(),
+        &[ctx.bumps.collateral_vault],
+    ];
+    let signer_seeds = &[&collateral_seeds[..]];
+
+    // 4. Transfer the collateral lot to bidder
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.collateral_vault.to_account_info(),
+        to: ctx.accounts.bidder_collateral_token_account.to_account_info(),
+        authority: ctx.accounts.collateral_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, ctx.accounts.collateral_vault.amount)?;
+
+    // Close collateral vault account
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.collateral_vault.to_account_info(),
+        destination: ctx.accounts.lender.to_account_info(),
+        authority: ctx.accounts.collateral_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::close_account(cpi_ctx)?;
+
+    emit!(CollateralAuctionFilled {
+        auction: ctx.accounts.collateral_auction.key(),
+        loan: loan_key,
+        bidder: ctx.accounts.bidder.key(),
+        price,
+        lender_received,
+        lender_fee,
+        borrower_received,
+    });
+
+    Ok(())
+}
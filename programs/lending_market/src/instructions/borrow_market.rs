@@ -0,0 +1,422 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount};
+use anchor_spl::token::{self, InitializeAccount3, Token, TokenAccount, Transfer};
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::instructions::cancel_lending_offer::LendingOffer;
+use crate::oracle::OraclePrice;
+use crate::state::{InterestMode, Loan, Orderbook};
+use crate::error::ErrorCode;
+
+/// Remaining accounts per offer: lending_offer, escrow, loan, collateral_vault
+pub const MARKET_ORDER_ACCOUNTS: usize = 4;
+
+#[event]
+pub struct MarketBorrowFilled {
+    pub borrower: Pubkey,
+    pub asset_pair_market: Pubkey,
+    pub amount: u64,
+    pub collateral_amount: u64,
+    pub loans: u64,
+}
+
+#[derive(Accounts)]
+pub struct BorrowMarket<'info> {
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Box<Account<'info, LendingMarket>>,
+
+    #[account(
+        mut,
+        constraint = asset_pair_market.is_active @ ErrorCode::MarketNotActive,
+        constraint = asset_pair_market.collateral_mint == collateral_mint.key() @ ErrorCode::InvalidAssetPair,
+    )]
+    pub asset_pair_market: Box<Account<'info, AssetPairMarket>>,
+
+    #[account(
+        mut,
+        seeds = [Orderbook::SEED,asset_pair_market.key().as_ref()],
+        bump,
+    )]
+    pub orderbook: AccountLoader<'info, Orderbook>,
+
+    /// CHECK: Validated against asset_pair_market
+    pub collateral_mint: AccountInfo<'info>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = loan_price_feed.key() == asset_pair_market.loan_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub loan_price_feed: AccountInfo<'info>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = collateral_price_feed.key() == asset_pair_market.collateral_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub collateral_price_feed: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = borrower_loan_token_account.owner == borrower.key(),
+        constraint = borrower_loan_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub borrower_loan_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = borrower_collateral_token_account.owner == borrower.key(),
+        constraint = borrower_collateral_token_account.mint == collateral_mint.key(),
+    )]
+    pub borrower_collateral_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated in lending_market
+    #[account(
+        mut,
+        constraint = fee_recipient.key() == lending_market.fee_recipient @ ErrorCode::InvalidFeeRecipient,
+    )]
+    pub fee_recipient: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = fee_recipient_token_account.owner == fee_recipient.key(),
+        constraint = fee_recipient_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub fee_recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Fill `amount` from the offers in remaining accounts, in the order given (best first, e.g. from the Orderbook)
+/// 1. Skip offers that are inactive, expired, priced above `max_interest_rate_bps` or whose minimum fill can't be met
+/// 2. Take min(unfilled, remaining) from each offer, with the collateral that offer's LTV requires
+///    (the last loan also takes any excess collateral)
+/// 3. Open one Loan per filled offer exactly as take_loan would
+/// 4. Fail the whole transaction unless `amount` is filled
+pub fn borrow_market_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BorrowMarket<'info>>,
+    amount: u64,
+    max_interest_rate_bps: u64,
+    collateral_amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidLoanAmount);
+    require!(collateral_amount > 0, ErrorCode::InvalidCollateralAmount);
+    require!(
+        ctx.remaining_accounts.len().is_multiple_of(MARKET_ORDER_ACCOUNTS),
+        ErrorCode::InvalidRemainingAccounts
+    );
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let loan_price = OraclePrice::load(&ctx.accounts.loan_price_feed, &ctx.accounts.asset_pair_market.loan_feed_id, current_time)?;
+    let collateral_price = OraclePrice::load(&ctx.accounts.collateral_price_feed, &ctx.accounts.asset_pair_market.collateral_feed_id, current_time)?;
+
+    // Checkpoint the variable rate before utilization moves
+    ctx.accounts.asset_pair_market.update_rate_index(current_time)?;
+    let asset_pair_key = ctx.accounts.asset_pair_market.key();
+
+    let mut filled: u64 = 0;
+    let mut collateral_used: u64 = 0;
+    let mut loans: u64 = 0;
+
+    for accounts in ctx.remaining_accounts.chunks(MARKET_ORDER_ACCOUNTS) {
+        if filled == amount {
+            break;
+        }
+
+        let mut lending_offer: Account<'info, LendingOffer> = Account::try_from(&accounts[0])?;
+        require_keys_eq!(lending_offer.asset_pair_market, asset_pair_key, ErrorCode::InvalidAssetPair);
+
+        let interest_rate_bps = match lending_offer.interest_mode {
+            InterestMode::Variable => ctx.accounts.asset_pair_market.variable_rate_bps(),
+            _ => lending_offer.interest_rate_bps,
+        };
+        if !lending_offer.is_active
+            || lending_offer.is_expired(current_time)
+            || interest_rate_bps > max_interest_rate_bps
+        {
+            continue;
+        }
+
+        let borrow_amount = (amount - filled).min(lending_offer.remaining_amount);
+        // Same minimum fill rule as take_loan, an offer that can't be used is skipped
+        if borrow_amount < lending_offer.min_fill_amount && borrow_amount != lending_offer.remaining_amount {
+            continue;
+        }
+        filled += borrow_amount;
+
+        // Each loan gets the collateral its offer's LTV requires, the last one also takes the excess
+        let asset_pair_market = &ctx.accounts.asset_pair_market;
+        // The pair's liquidation LTV may have been lowered since the offer was created
+        require!(lending_offer.ltv_bps < asset_pair_market.liquidation_ltv_bps, ErrorCode::InvalidLTV);
+        let required_collateral = asset_pair_market.required_collateral(
+            borrow_amount,
+            lending_offer.ltv_bps,
+            &loan_price,
+            &collateral_price,
+        )?;
+        let collateral_left = collateral_amount - collateral_used;
+        require!(
+            collateral_left >= required_collateral,
+            ErrorCode::InvalidCollateralAmount
+        );
+        let loan_collateral = if filled == amount { collateral_left } else { required_collateral };
+        collateral_used += loan_collateral;
+
+        let loan_value = loan_price.value(borrow_amount, asset_pair_market.loan_decimals)?;
+        let collateral_value =
+            collateral_price.value(loan_collateral, asset_pair_market.collateral_decimals)?;
+
+        open_market_loan(
+            ctx.accounts,
+            &lending_offer,
+            &accounts[1..],
+            borrow_amount,
+            loan_collateral,
+            loan_value,
+            collateral_value,
+            current_time,
+        )?;
+
+        // Reduce remaining offer amount, mark offer inactive once fully taken and update the orderbook
+        lending_offer.remaining_amount -= borrow_amount;
+        if lending_offer.remaining_amount == 0 {
+            lending_offer.is_active = false;
+        }
+        ctx.accounts
+            .orderbook
+            .load_mut()?
+            .update_remaining(&lending_offer.key(), lending_offer.remaining_amount);
+        lending_offer.exit(&crate::ID)?;
+
+        let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+        asset_pair_market.total_available = asset_pair_market.total_available.saturating_sub(borrow_amount);
+        asset_pair_market.total_borrowed = asset_pair_market.total_borrowed.saturating_add(borrow_amount);
+        loans += 1;
+    }
+
+    require!(filled == amount, ErrorCode::OrderNotFilled);
+
+    emit!(MarketBorrowFilled {
+        borrower: ctx.accounts.borrower.key(),
+        asset_pair_market: asset_pair_key,
+        amount,
+        collateral_amount,
+        loans,
+    });
+
+    Ok(())
+}
+
+/// Create the Loan and collateral vault PDAs of one fill (same seeds as take_loan), move the collateral in,
+/// and pay the borrower from the offer's escrow minus the borrower fee
+#[allow(clippy::too_many_arguments)]
+fn open_market_loan<'info>(
+    ctx: &BorrowMarket<'info>,
+    lending_offer: &Account<'info, LendingOffer>,
+    accounts: &[AccountInfo<'info>],
+    borrow_amount: u64,
+    collateral_amount: u64,
+    loan_value: u128,
+    collateral_value: u128,
+    current_time: i64,
+) -> Result<()> {
+    let (escrow, loan_info, collateral_vault) = (&accounts[0], &accounts[1], &accounts[2]);
+    let lending_offer_key = lending_offer.key();
+    let borrower_key = ctx.borrower.key();
+
+    let (escrow_key, escrow_bump) = Pubkey::find_program_address(
+        &[LendingOffer::ESCROW_SEED, lending_offer_key.as_ref()],
+        &crate::ID,
+    );
+    let (loan_key, loan_bump) = Pubkey::find_program_address(
+        &[Loan::SEED, lending_offer_key.as_ref(), borrower_key.as_ref()],
+        &crate::ID,
+    );
+    let (collateral_vault_key, collateral_vault_bump) = Pubkey::find_program_address(
+        &[Loan::COLLATERAL_SEED, loan_key.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(escrow.key(), escrow_key, ErrorCode::InvalidRemainingAccounts);
+    require_keys_eq!(loan_info.key(), loan_key, ErrorCode::InvalidRemainingAccounts);
+    require_keys_eq!(collateral_vault.key(), collateral_vault_key, ErrorCode::InvalidRemainingAccounts);
+
+    // Create the loan account
+    let loan_seeds = &[
+        Loan::SEED,
+        lending_offer_key.as_ref(),
+        borrower_key.as_ref(),
+        &[loan_bump],
+    ];
+    create_pda_account(
+        ctx,
+        loan_info,
+        8 + Loan::INIT_SPACE,
+        &crate::ID,
+        &[&loan_seeds[..]],
+    )?;
+
+    // Create the collateral vault, owned by itself like in take_loan
+    let collateral_seeds = &[
+        Loan::COLLATERAL_SEED,
+        loan_key.as_ref(),
+        &[collateral_vault_bump],
+    ];
+    create_pda_account(
+        ctx,
+        collateral_vault,
+        TokenAccount::LEN,
+        &ctx.token_program.key(),
+        &[&collateral_seeds[..]],
+    )?;
+
+    let cpi_accounts = InitializeAccount3 {
+        account: collateral_vault.clone(),
+        mint: ctx.collateral_mint.to_account_info(),
+        authority: collateral_vault.clone(),
+    };
+    token::initialize_account3(CpiContext::new(ctx.token_program.to_account_info(), cpi_accounts))?;
+
+    // Transfer collateral from borrower to collateral vault
+    let cpi_accounts = Transfer {
+        from: ctx.borrower_collateral_token_account.to_account_info(),
+        to: collateral_vault.clone(),
+        authority: ctx.borrower.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, collateral_amount)?;
+
+    // Calculate borrower fee (1%)
+    let borrower_fee = borrow_amount
+        .checked_mul(ctx.lending_market.borrower_fee_bps)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?
+        .checked_div(10000)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+
+    let borrower_receives = borrow_amount
+        .checked_sub(borrower_fee)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+
+    // Create escrow authority seeds
+    let escrow_seeds = &[
+        LendingOffer::ESCROW_SEED,
+        lending_offer_key.as_ref(),
+        &[escrow_bump],
+    ];
+    let signer_seeds = &[&escrow_seeds[..]];
+
+    // Transfer loan amount (minus fee) from escrow to borrower
+    let cpi_accounts = Transfer {
+        from: escrow.clone(),
+        to: ctx.borrower_loan_token_account.to_account_info(),
+        authority: escrow.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, borrower_receives)?;
+
+    // Transfer fee from escrow to fee recipient
+    let cpi_accounts = Transfer {
+        from: escrow.clone(),
+        to: ctx.fee_recipient_token_account.to_account_info(),
+        authority: escrow.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, borrower_fee)?;
+
+    // Initialize loan
+    let loan = Loan {
+        lending_offer: lending_offer_key,
+        asset_pair_market: lending_offer.asset_pair_market,
+        lender: lending_offer.lender,
+        borrower: borrower_key,
+        principal_amount: borrow_amount,
+        collateral_amount,
+        interest_rate_bps: lending_offer.interest_rate_bps,
+        interest_mode: lending_offer.interest_mode,
+        penalty_rate_bps: lending_offer.penalty_rate_bps,
+        notice_duration: lending_offer.notice_duration,
+        ltv_bps: lending_offer.ltv_bps,
+        origination_loan_value: u64::try_from(loan_value)
+            .map_err(|_| ErrorCode::PriceCalculationOverflow)?,
+        origination_collateral_value: u64::try_from(collateral_value)
+            .map_err(|_| ErrorCode::PriceCalculationOverflow)?,
+        loan_start_time: current_time,
+        last_interest_update: current_time,
+        accrued_interest: 0,
+        last_rate_index: ctx.asset_pair_market.rate_index,
+        repayment_deadline: None,
+        maturity: lending_offer.maturity(current_time)?,
+        is_active: true,
+        bump: loan_bump,
+    };
+    loan.try_serialize(&mut &mut loan_info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+/// Create a PDA like Anchor's `init`: an address that already holds lamports (anyone can send some to a
+/// derivable PDA) is topped up to rent exemption, allocated and assigned instead of failing create_account
+fn create_pda_account<'info>(
+    ctx: &BorrowMarket<'info>,
+    account: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(space);
+    let current_lamports = account.lamports();
+
+    if current_lamports == 0 {
+        let cpi_accounts = CreateAccount {
+            from: ctx.borrower.to_account_info(),
+            to: account.clone(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.system_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        return system_program::create_account(cpi_ctx, rent_exempt_lamports, space as u64, owner);
+    }
+
+    let missing_lamports = rent_exempt_lamports.saturating_sub(current_lamports);
+    if missing_lamports > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.borrower.to_account_info(),
+            to: account.clone(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_ctx, missing_lamports)?;
+    }
+
+    let cpi_accounts = Allocate {
+        account_to_allocate: account.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.system_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    system_program::allocate(cpi_ctx, space as u64)?;
+
+    let cpi_accounts = Assign {
+        account_to_assign: account.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.system_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    system_program::assign(cpi_ctx, owner)
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer, CloseAccount};
+use crate::instructions::create_borrow_request::BorrowRequest;
+
+/// Available while the protocol is paused so borrowers can always withdraw their collateral
+#[derive(Accounts)]
+pub struct CancelBorrowRequest<'info> {
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+
+    #[account(
+        mut,
+        close = borrower,
+        has_one = borrower,
+    )]
+    pub borrow_request: Account<'info, BorrowRequest>,
+
+    #[account(
+        mut,
+        seeds = [BorrowRequest::COLLATERAL_SEED,borrow_request.key().as_ref()],
+        bump,
+    )]
+    pub collateral_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = borrower_collateral_token_account.owner == borrower.key(),
+    )]
+    pub borrower_collateral_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn cancel_borrow_request_handler(ctx: Context<CancelBorrowRequest>) -> Result<()> {
+    let request_key = ctx.accounts.borrow_request.key();
+
+    // Create collateral escrow authority seeds
+    let escrow_seeds = &[
+        BorrowRequest::COLLATERAL_SEED,
+        request_key.as_ref(),
+        &[ctx.bumps.collateral_escrow],
+    ];
+    let signer_seeds = &[&escrow_seeds[..]];
+
+    // Transfer collateral back from escrow to borrower
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.collateral_escrow.to_account_info(),
+        to: ctx.accounts.borrower_collateral_token_account.to_account_info(),
+        authority: ctx.accounts.collateral_escrow.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, ctx.accounts.collateral_escrow.amount)?;
+
+    // Close collateral escrow account
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.collateral_escrow.to_account_info(),
+        destination: ctx.accounts.borrower.to_account_info(),
+        authority: ctx.accounts.collateral_escrow.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::close_account(cpi_ctx)?;
+
+    Ok(())
+}
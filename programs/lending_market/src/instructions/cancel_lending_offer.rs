@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer, CloseAccount};
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::state::{InterestMode, Orderbook};
 use crate::error::ErrorCode;
 
 #[account]
@@ -8,20 +10,43 @@ pub struct LendingOffer {
     pub lender: Pubkey,
     pub asset_pair_market: Pubkey,
     pub loan_amount: u64,
-    pub interest_rate_bps: u64,  // e.g., 500 = 5% APR
+    pub remaining_amount: u64,    // still in escrow, offer stays active until it reaches 0
+    pub min_fill_amount: u64,     // smallest amount a single borrower can take
+    pub interest_rate_bps: u64,  // e.g., 500 = 5% APR, 0 for InterestMode::Variable
+    pub interest_mode: InterestMode,
+    pub penalty_rate_bps: u64,    // extra APR on principal once the repayment deadline has passed
+    pub notice_duration: i64,     // seconds between request_repayment and the deadline
+    #[max_len(1)]
+    pub duration: Option<i64>,    // seconds until maturity for fixed-term loans, None for perpetual
     pub ltv_bps: u64,             // e.g., 8000 = 80% LTV
     pub offer_id: u64,
     pub is_active: bool,
     pub created_at: i64,
+    #[max_len(1)]
+    pub expires_at: Option<i64>,  // can't be taken from this time on, anyone may close_expired_offer
     pub bump: u8,
 }
 
 impl LendingOffer {
     pub const SEED: &'static [u8] = b"lending_offer";
     pub const ESCROW_SEED: &'static [u8] = b"escrow";
+    pub const MAX_DURATION: i64 = 10 * 365 * 24 * 60 * 60; // upper limit for fixed-term loans, 10 years
+
+    /// Maturity of a loan taken at `current_time`, None for perpetual offers
+    pub fn maturity(&self, current_time: i64) -> Result<Option<i64>> {
+        self.duration
+            .map(|duration| current_time.checked_add(duration).ok_or(error!(ErrorCode::MathOverflow)))
+            .transpose()
+    }
+
+    pub fn is_expired(&self, current_time: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| current_time >= expires_at)
+    }
 }
 
 
+/// Available while the protocol is paused so lenders can always withdraw.
+/// A fully drained offer is inactive but can still be cancelled to reclaim its rent.
 #[derive(Accounts)]
 pub struct CancelLendingOffer<'info> {
     #[account(mut)]
@@ -31,14 +56,25 @@ pub struct CancelLendingOffer<'info> {
         mut,
         close = lender,
         has_one = lender,
-        constraint = lending_offer.is_active @ ErrorCode::OfferNotActive,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
     )]
     pub lending_offer: Account<'info, LendingOffer>,
 
+    #[account(mut)]
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+
+    #[account(
+        mut,
+        seeds = [Orderbook::SEED,asset_pair_market.key().as_ref()],
+        bump,
+    )]
+    pub orderbook: AccountLoader<'info, Orderbook>,
+
     #[account(
         mut,
         seeds = [LendingOffer::ESCROW_SEED,lending_offer.key().as_ref()],
         bump,
+        constraint = lending_offer.is_active || escrow.amount == 0 @ ErrorCode::OfferNotActive,
     )]
     pub escrow: Account<'info, TokenAccount>,
 
@@ -55,6 +91,12 @@ pub fn cancel_lending_offer_handler(ctx: Context<CancelLendingOffer>) -> Result<
     let lending_offer = &ctx.accounts.lending_offer;
     let offer_key = lending_offer.key();
 
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+    asset_pair_market.update_rate_index(Clock::get()?.unix_timestamp)?;
+    asset_pair_market.total_available =
+        asset_pair_market.total_available.saturating_sub(lending_offer.remaining_amount);
+    ctx.accounts.orderbook.load_mut()?.remove(&offer_key);
+
     // Create escrow authority seeds
     let escrow_seeds = &[
         LendingOffer::ESCROW_SEED,
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::state::Loan;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct RepaymentRequestCancelled {
+    pub loan: Pubkey,
+    pub lender: Pubkey,
+    pub borrower: Pubkey,
+    pub repayment_deadline: i64,
+}
+
+// Only relaxes the borrower's position, so not checked against the emergency pause
+#[derive(Accounts)]
+pub struct CancelRepaymentRequest<'info> {
+    pub lender: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = lender,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        constraint = loan.is_active @ ErrorCode::LoanNotActive,
+    )]
+    pub loan: Account<'info, Loan>,
+
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+}
+
+/// Penalty interest accrued past the deadline so far stays owed
+pub fn cancel_repayment_request_handler(ctx: Context<CancelRepaymentRequest>) -> Result<()> {
+    let loan = &mut ctx.accounts.loan;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let repayment_deadline = loan.repayment_deadline.ok_or(ErrorCode::RepaymentDeadlineNotSet)?;
+
+    loan.accrue_interest(current_time, &ctx.accounts.asset_pair_market)?;
+    loan.repayment_deadline = None;
+
+    emit!(RepaymentRequestCancelled {
+        loan: loan.key(),
+        lender: loan.lender,
+        borrower: loan.borrower,
+        repayment_deadline,
+    });
+
+    Ok(())
+}
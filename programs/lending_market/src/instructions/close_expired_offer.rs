@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer, CloseAccount};
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::instructions::cancel_lending_offer::LendingOffer;
+use crate::state::Orderbook;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct ExpiredOfferClosed {
+    pub lending_offer: Pubkey,
+    pub lender: Pubkey,
+    pub closed_by: Pubkey,
+    pub returned_amount: u64,
+}
+
+/// Permissionless crank, also available while the protocol is paused
+#[derive(Accounts)]
+pub struct CloseExpiredOffer<'info> {
+    pub cranker: Signer<'info>,
+
+    /// CHECK: Validated against lending_offer, receives the rent
+    #[account(mut)]
+    pub lender: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        close = lender,
+        has_one = lender,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        constraint = lending_offer.is_expired(Clock::get()?.unix_timestamp) @ ErrorCode::OfferNotExpired,
+    )]
+    pub lending_offer: Account<'info, LendingOffer>,
+
+    #[account(mut)]
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+
+    #[account(
+        mut,
+        seeds = [Orderbook::SEED,asset_pair_market.key().as_ref()],
+        bump,
+    )]
+    pub orderbook: AccountLoader<'info, Orderbook>,
+
+    #[account(
+        mut,
+        seeds = [LendingOffer::ESCROW_SEED,lending_offer.key().as_ref()],
+        bump,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = lender_token_account.owner == lender.key(),
+        constraint = lender_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub lender_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn close_expired_offer_handler(ctx: Context<CloseExpiredOffer>) -> Result<()> {
+    let lending_offer = &ctx.accounts.lending_offer;
+    let offer_key = lending_offer.key();
+    let returned_amount = ctx.accounts.escrow.amount;
+
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+    asset_pair_market.update_rate_index(Clock::get()?.unix_timestamp)?;
+    asset_pair_market.total_available =
+        asset_pair_market.total_available.saturating_sub(lending_offer.remaining_amount);
+    ctx.accounts.orderbook.load_mut()?.remove(&offer_key);
+
+    // Create escrow authority seeds
+    let escrow_seeds = &[
+        LendingOffer::ESCROW_SEED,
+        offer_key.as_ref(),
+        &[ctx.bumps.escrow],
+    ];
+    let signer_seeds = &[&escrow_seeds[..]];
+
+    // Transfer tokens back from escrow to lender
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.escrow.to_account_info(),
+        to: ctx.accounts.lender_token_account.to_account_info(),
+        authority: ctx.accounts.escrow.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, returned_amount)?;
+
+    // Close escrow account, rent goes to the lender
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.escrow.to_account_info(),
+        destination: ctx.accounts.lender.to_account_info(),
+        authority: ctx.accounts.escrow.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::close_account(cpi_ctx)?;
+
+    emit!(ExpiredOfferClosed {
+        lending_offer: offer_key,
+        lender: ctx.accounts.lender.key(),
+        closed_by: ctx.accounts.cranker.key(),
+        returned_amount,
+    });
+
+    Ok(())
+}
@@ -1,12 +1,63 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
 use crate::instructions::init_lending_market::LendingMarket;
+use crate::oracle::OraclePrice;
+use crate::state::{Loan, Orderbook};
+use crate::math::WAD;
 use crate::error::ErrorCode;
 
+/// Kinked utilization curve for variable rate offers, rates in bps APR
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct RateModel {
+    pub base_rate_bps: u64,    // rate at 0% utilization
+    pub slope_bps: u64,        // added linearly from 0% up to the kink
+    pub jump_slope_bps: u64,   // added linearly from the kink up to 100%
+    pub kink_bps: u64,         // utilization where the jump slope starts
+}
+
+impl RateModel {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.kink_bps > 0 && self.kink_bps < 10000, ErrorCode::InvalidRateModel);
+        let max_rate_bps = self.base_rate_bps
+            .checked_add(self.slope_bps)
+            .and_then(|x| x.checked_add(self.jump_slope_bps))
+            .ok_or(ErrorCode::InvalidRateModel)?;
+        require!(max_rate_bps <= 10000, ErrorCode::InvalidRateModel); // same cap as fixed rate offers
+        Ok(())
+    }
+
+    /// Rate in bps APR at `utilization_bps`
+    pub fn rate_bps(&self, utilization_bps: u64) -> u64 {
+        if utilization_bps <= self.kink_bps {
+            self.base_rate_bps + self.slope_bps * utilization_bps / self.kink_bps
+        } else {
+            self.base_rate_bps
+                + self.slope_bps
+                + self.jump_slope_bps * (utilization_bps - self.kink_bps) / (10000 - self.kink_bps)
+        }
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct AssetPairMarket {
     pub loan_mint: Pubkey,
     pub collateral_mint: Pubkey,
+    pub loan_decimals: u8,
+    pub collateral_decimals: u8,
+    pub loan_price_feed: Pubkey,        // Pyth PriceUpdateV2 or MockPriceFeed
+    pub collateral_price_feed: Pubkey,  // Pyth PriceUpdateV2 or MockPriceFeed
+    pub loan_feed_id: [u8; 32],         // Pyth feed id the loan price account must carry (the mint for a mock feed)
+    pub collateral_feed_id: [u8; 32],   // Pyth feed id the collateral price account must carry (the mint for a mock feed)
+    pub liquidation_ltv_bps: u64,       // loans above this oracle-priced LTV can be liquidated
+    pub liquidation_penalty_bps: u64,   // extra collateral seized on top of the debt
+    pub liquidation_bonus_bps: u64,     // discount on collateral for keepers repaying unhealthy loans
+    #[max_len(1)]
+    pub rate_model: Option<RateModel>,  // set by admin, enables variable rate offers
+    pub total_available: u64,           // loan tokens left in active offers
+    pub total_borrowed: u64,            // principal outstanding on active loans
+    pub rate_index: u128,               // variable rate integrated over time (WAD), never decreases
+    pub last_rate_update: i64,          // checkpoint of rate_index
     pub is_active: bool,
     pub bump: u8,
 }
@@ -14,6 +65,102 @@ pub struct AssetPairMarket {
 /// seeds = [AssetPairMarket::SEED, loan_mint.key().as_ref(), collateral_mint.key().as_ref()]
 impl AssetPairMarket {
     pub const SEED: &'static [u8] = b"asset_pair";
+    pub const DEFAULT_LIQUIDATION_PENALTY_BPS: u64 = 500; // 5%
+    pub const MAX_LIQUIDATION_PENALTY_BPS: u64 = 2000;    // 20%
+    pub const DEFAULT_LIQUIDATION_BONUS_BPS: u64 = 500;   // 5%
+    pub const MAX_LIQUIDATION_BONUS_BPS: u64 = 2000;      // 20%
+
+    /// Share of the pair's liquidity that is lent out, in bps
+    pub fn utilization_bps(&self) -> u64 {
+        let total = self.total_borrowed as u128 + self.total_available as u128;
+        if total == 0 {
+            return 0;
+        }
+        (self.total_borrowed as u128 * 10000 / total) as u64
+    }
+
+    /// Current variable rate in bps APR, 0 without a rate model
+    pub fn variable_rate_bps(&self) -> u64 {
+        self.rate_model.map_or(0, |model| model.rate_bps(self.utilization_bps()))
+    }
+
+    /// `rate_index` brought forward to `current_time` at the current variable rate
+    pub fn current_rate_index(&self, current_time: i64) -> Result<u128> {
+        let time_elapsed = current_time.saturating_sub(self.last_rate_update).max(0) as u128;
+        let growth = self.variable_rate_bps() as u128 * WAD / 10000 * time_elapsed
+            / Loan::SECONDS_PER_YEAR as u128;
+
+        self.rate_index
+            .checked_add(growth)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    /// Checkpoint `rate_index` at `current_time`, must run before the totals or the rate model change
+    pub fn update_rate_index(&mut self, current_time: i64) -> Result<()> {
+        self.rate_index = self.current_rate_index(current_time)?;
+        self.last_rate_update = current_time;
+        Ok(())
+    }
+
+    /// LTV in bps of `debt` loan tokens against `collateral_amount` collateral tokens at oracle prices
+    pub fn ltv_bps(
+        &self,
+        debt: u64,
+        collateral_amount: u64,
+        loan_price: &OraclePrice,
+        collateral_price: &OraclePrice,
+    ) -> Result<u64> {
+        let debt_value = loan_price.value(debt, self.loan_decimals)?;
+        let collateral_value = collateral_price.value(collateral_amount, self.collateral_decimals)?;
+
+        if collateral_value == 0 {
+            return Ok(u64::MAX);
+        }
+
+        let ltv_bps = debt_value
+            .checked_mul(10000)
+            .ok_or(error!(ErrorCode::PriceCalculationOverflow))?
+            / collateral_value;
+
+        Ok(u64::try_from(ltv_bps).unwrap_or(u64::MAX))
+    }
+
+    /// Collateral tokens needed so that `loan_amount` loan tokens stay within `ltv_bps` (rounds up)
+    pub fn required_collateral(
+        &self,
+        loan_amount: u64,
+        ltv_bps: u64,
+        loan_price: &OraclePrice,
+        collateral_price: &OraclePrice,
+    ) -> Result<u64> {
+        require!(ltv_bps > 0, ErrorCode::InvalidLTV);
+
+        // Required collateral value = loan_value / ltv_bps * 10000
+        let required_value = loan_price
+            .value(loan_amount, self.loan_decimals)?
+            .checked_mul(10000)
+            .ok_or(error!(ErrorCode::PriceCalculationOverflow))?
+            .div_ceil(ltv_bps as u128);
+
+        collateral_price.amount_for_value(required_value, self.collateral_decimals)
+    }
+
+    /// Collateral tokens worth `debt` loan tokens plus `premium_bps` on top (rounds up)
+    pub fn collateral_for_debt(
+        &self,
+        debt: u64,
+        premium_bps: u64,
+        loan_price: &OraclePrice,
+        collateral_price: &OraclePrice,
+    ) -> Result<u64> {
+        let value = loan_price
+            .value(debt, self.loan_decimals)?
+            .checked_mul(10000 + premium_bps as u128)
+            .ok_or(error!(ErrorCode::PriceCalculationOverflow))?
+            .div_ceil(10000);
+
+        collateral_price.amount_for_value(value, self.collateral_decimals)
+    }
 }
 
 
@@ -40,22 +187,60 @@ pub struct CreateAssetPairMarket<'info> {
     )]
     pub asset_pair_market: Account<'info, AssetPairMarket>,
 
-    /// CHECK: Validated as mint in handler
-    pub loan_mint: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<Orderbook>(),
+        seeds = [Orderbook::SEED,asset_pair_market.key().as_ref()],
+        bump,
+    )]
+    pub orderbook: AccountLoader<'info, Orderbook>,
+
+    pub loan_mint: Account<'info, Mint>,
+
+    pub collateral_mint: Account<'info, Mint>,
 
-    /// CHECK: Validated as mint in handler
-    pub collateral_mint: AccountInfo<'info>,
+    /// CHECK: Validated as a price feed in handler
+    pub loan_price_feed: AccountInfo<'info>,
+
+    /// CHECK: Validated as a price feed in handler
+    pub collateral_price_feed: AccountInfo<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
-pub fn create_asset_pair_market_handler(ctx: Context<CreateAssetPairMarket>) -> Result<()> {
+pub fn create_asset_pair_market_handler(
+    ctx: Context<CreateAssetPairMarket>,
+    loan_feed_id: [u8; 32],
+    collateral_feed_id: [u8; 32],
+) -> Result<()> {
+    // Price feeds must be readable by the oracle layer and carry the expected feed ids before they are registered
+    OraclePrice::read(&ctx.accounts.loan_price_feed, &loan_feed_id)?;
+    OraclePrice::read(&ctx.accounts.collateral_price_feed, &collateral_feed_id)?;
+
     let asset_pair_market = &mut ctx.accounts.asset_pair_market;
 
     asset_pair_market.loan_mint = ctx.accounts.loan_mint.key();
     asset_pair_market.collateral_mint = ctx.accounts.collateral_mint.key();
+    asset_pair_market.loan_decimals = ctx.accounts.loan_mint.decimals;
+    asset_pair_market.collateral_decimals = ctx.accounts.collateral_mint.decimals;
+    asset_pair_market.loan_price_feed = ctx.accounts.loan_price_feed.key();
+    asset_pair_market.collateral_price_feed = ctx.accounts.collateral_price_feed.key();
+    asset_pair_market.loan_feed_id = loan_feed_id;
+    asset_pair_market.collateral_feed_id = collateral_feed_id;
+    asset_pair_market.liquidation_ltv_bps = Loan::LIQUIDATION_LTV_BPS;
+    asset_pair_market.liquidation_penalty_bps = AssetPairMarket::DEFAULT_LIQUIDATION_PENALTY_BPS;
+    asset_pair_market.liquidation_bonus_bps = AssetPairMarket::DEFAULT_LIQUIDATION_BONUS_BPS;
+    asset_pair_market.rate_model = None;
+    asset_pair_market.total_available = 0;
+    asset_pair_market.total_borrowed = 0;
+    asset_pair_market.rate_index = 0;
+    asset_pair_market.last_rate_update = Clock::get()?.unix_timestamp;
     asset_pair_market.is_active = true;
     asset_pair_market.bump = ctx.bumps.asset_pair_market;
 
+    let mut orderbook = ctx.accounts.orderbook.load_init()?;
+    orderbook.asset_pair_market = ctx.accounts.asset_pair_market.key();
+
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::state::Loan;
+use crate::error::ErrorCode;
+
+/// Borrower-side order: collateral is escrowed up front and any lender can fund it with fill_borrow_request.
+/// The request also holds the rent of the Loan and collateral vault a fill opens, so the lender is reimbursed.
+#[account]
+#[derive(InitSpace)]
+pub struct BorrowRequest {
+    pub borrower: Pubkey,
+    pub asset_pair_market: Pubkey,
+    pub request_id: u64,
+    pub loan_amount: u64,             // filled in full by a single lender
+    pub collateral_amount: u64,       // held in the request's collateral escrow
+    pub max_interest_rate_bps: u64,   // highest simple APR the borrower accepts
+    pub ltv_bps: u64,                 // e.g., 8000 = 80% LTV, checked at oracle prices on fill
+    pub notice_duration: i64,         // seconds between request_repayment and the deadline
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+/// seeds = [BorrowRequest::SEED, borrower.key().as_ref(), request_id.to_le_bytes().as_ref()]
+impl BorrowRequest {
+    pub const SEED: &'static [u8] = b"borrow_request";
+    pub const COLLATERAL_SEED: &'static [u8] = b"request_collateral";
+
+    /// Rent of the Loan and collateral vault opened on fill, pre-funded by the borrower
+    pub fn loan_rent(rent: &Rent) -> u64 {
+        rent.minimum_balance(8 + Loan::INIT_SPACE) + rent.minimum_balance(TokenAccount::LEN)
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(request_id: u64)]
+pub struct CreateBorrowRequest<'info> {
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
+    #[account(
+        constraint = asset_pair_market.is_active @ ErrorCode::MarketNotActive,
+        constraint = asset_pair_market.collateral_mint == collateral_mint.key() @ ErrorCode::InvalidAssetPair,
+    )]
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+
+    #[account(
+        init,
+        payer = borrower,
+        space = 8 + BorrowRequest::INIT_SPACE,
+        seeds = [
+            BorrowRequest::SEED,
+            borrower.key().as_ref(),
+            request_id.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub borrow_request: Account<'info, BorrowRequest>,
+
+    #[account(
+        init,
+        payer = borrower,
+        token::mint = collateral_mint,
+        token::authority = collateral_escrow,
+        seeds = [
+            BorrowRequest::COLLATERAL_SEED,
+            borrow_request.key().as_ref()
+        ],
+        bump,
+    )]
+    pub collateral_escrow: Account<'info, TokenAccount>,
+
+    /// CHECK: Validated against asset_pair_market
+    pub collateral_mint: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = borrower_collateral_token_account.owner == borrower.key(),
+        constraint = borrower_collateral_token_account.mint == collateral_mint.key(),
+    )]
+    pub borrower_collateral_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_borrow_request_handler(
+    ctx: Context<CreateBorrowRequest>,
+    request_id: u64,
+    loan_amount: u64,
+    collateral_amount: u64,
+    max_interest_rate_bps: u64,
+    ltv_bps: u64,
+    notice_duration: i64,
+) -> Result<()> {
+    let lending_market = &ctx.accounts.lending_market;
+
+    require!(loan_amount > 0, ErrorCode::InvalidLoanAmount);
+    require!(collateral_amount > 0, ErrorCode::InvalidCollateralAmount);
+    require!(max_interest_rate_bps <= 10000, ErrorCode::InvalidInterestRate);
+    require!(ltv_bps > 0 && ltv_bps <= 10000, ErrorCode::InvalidLTV);
+    // Loans must not be liquidatable at origination
+    require!(ltv_bps < ctx.accounts.asset_pair_market.liquidation_ltv_bps, ErrorCode::InvalidLTV);
+    require!(
+        notice_duration >= lending_market.min_notice_duration
+            && notice_duration <= lending_market.max_notice_duration,
+        ErrorCode::InvalidNoticeDuration
+    );
+
+    // Transfer collateral from borrower to the request's escrow
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.borrower_collateral_token_account.to_account_info(),
+        to: ctx.accounts.collateral_escrow.to_account_info(),
+        authority: ctx.accounts.borrower.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, collateral_amount)?;
+
+    // Pre-fund the rent of the loan accounts, repay_loan closes them to the borrower
+    let cpi_accounts = system_program::Transfer {
+        from: ctx.accounts.borrower.to_account_info(),
+        to: ctx.accounts.borrow_request.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+    system_program::transfer(cpi_ctx, BorrowRequest::loan_rent(&Rent::get()?))?;
+
+    let borrow_request = &mut ctx.accounts.borrow_request;
+    borrow_request.borrower = ctx.accounts.borrower.key();
+    borrow_request.asset_pair_market = ctx.accounts.asset_pair_market.key();
+    borrow_request.request_id = request_id;
+    borrow_request.loan_amount = loan_amount;
+    borrow_request.collateral_amount = collateral_amount;
+    borrow_request.max_interest_rate_bps = max_interest_rate_bps;
+    borrow_request.ltv_bps = ltv_bps;
+    borrow_request.notice_duration = notice_duration;
+    borrow_request.created_at = Clock::get()?.unix_timestamp;
+    borrow_request.bump = ctx.bumps.borrow_request;
+
+    Ok(())
+}
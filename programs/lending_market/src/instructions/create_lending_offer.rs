@@ -1,21 +1,36 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::instructions::init_lending_market::LendingMarket;
 use crate::instructions::create_asset_pair_market::AssetPairMarket;
 use crate::instructions::cancel_lending_offer::LendingOffer;
+use crate::state::{InterestMode, Orderbook, OrderbookEntry};
 use crate::error::ErrorCode;
 
 #[derive(Accounts)]
-#[instruction(offer_id: u64, loan_amount: u64, interest_rate_bps: u64, ltv_bps: u64)]
+#[instruction(offer_id: u64)]
 pub struct CreateLendingOffer<'info> {
     #[account(mut)]
     pub lender: Signer<'info>,
 
     #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
+    #[account(
+        mut,
         constraint = asset_pair_market.is_active @ ErrorCode::MarketNotActive,
         constraint = asset_pair_market.loan_mint == loan_mint.key() @ ErrorCode::InvalidAssetPair,
     )]
     pub asset_pair_market: Account<'info, AssetPairMarket>,
 
+    #[account(
+        mut,
+        seeds = [Orderbook::SEED,asset_pair_market.key().as_ref()],
+        bump,
+    )]
+    pub orderbook: AccountLoader<'info, Orderbook>,
+
     #[account(
         init,
         payer = lender,
@@ -56,16 +71,46 @@ pub struct CreateLendingOffer<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_lending_offer_handler(
     ctx: Context<CreateLendingOffer>,
     offer_id: u64,
     loan_amount: u64,
     interest_rate_bps: u64,
     ltv_bps: u64,
+    min_fill_amount: u64,
+    interest_mode: InterestMode,
+    penalty_rate_bps: u64,
+    notice_duration: i64,
+    duration: Option<i64>,
+    expires_at: Option<i64>,
 ) -> Result<()> {
     require!(loan_amount > 0, ErrorCode::InvalidLoanAmount);
+    require!(min_fill_amount <= loan_amount, ErrorCode::InvalidLoanAmount);
     require!(interest_rate_bps <= 10000, ErrorCode::InvalidInterestRate);
+    require!(penalty_rate_bps <= 10000, ErrorCode::InvalidInterestRate);
+    require!(
+        notice_duration >= ctx.accounts.lending_market.min_notice_duration
+            && notice_duration <= ctx.accounts.lending_market.max_notice_duration,
+        ErrorCode::InvalidNoticeDuration
+    );
+    if let Some(duration) = duration {
+        require!(
+            duration > 0 && duration <= LendingOffer::MAX_DURATION,
+            ErrorCode::InvalidLoanDuration
+        );
+    }
+    if let Some(expires_at) = expires_at {
+        require!(expires_at > Clock::get()?.unix_timestamp, ErrorCode::InvalidOfferExpiration);
+    }
     require!(ltv_bps > 0 && ltv_bps <= 10000, ErrorCode::InvalidLTV);
+    // Loans must not be liquidatable at origination
+    require!(ltv_bps < ctx.accounts.asset_pair_market.liquidation_ltv_bps, ErrorCode::InvalidLTV);
+    if interest_mode == InterestMode::Variable {
+        // The rate comes from the pair's curve, a fixed rate would be misleading
+        require!(ctx.accounts.asset_pair_market.rate_model.is_some(), ErrorCode::VariableRateNotEnabled);
+        require!(interest_rate_bps == 0, ErrorCode::InvalidInterestRate);
+    }
 
     // Transfer loan tokens from lender to escrow
     let cpi_accounts = Transfer {
@@ -76,17 +121,39 @@ pub fn create_lending_offer_handler(
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
     token::transfer(cpi_ctx, loan_amount)?;
 
+    let current_time = Clock::get()?.unix_timestamp;
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+    asset_pair_market.update_rate_index(current_time)?;
+    asset_pair_market.total_available = asset_pair_market.total_available.saturating_add(loan_amount);
+
     // Initialize lending offer
     let lending_offer = &mut ctx.accounts.lending_offer;
     lending_offer.lender = ctx.accounts.lender.key();
     lending_offer.asset_pair_market = ctx.accounts.asset_pair_market.key();
     lending_offer.loan_amount = loan_amount;
+    lending_offer.remaining_amount = loan_amount;
+    lending_offer.min_fill_amount = min_fill_amount;
     lending_offer.interest_rate_bps = interest_rate_bps;
+    lending_offer.interest_mode = interest_mode;
+    lending_offer.penalty_rate_bps = penalty_rate_bps;
+    lending_offer.notice_duration = notice_duration;
+    lending_offer.duration = duration;
     lending_offer.ltv_bps = ltv_bps;
     lending_offer.offer_id = offer_id;
     lending_offer.is_active = true;
-    lending_offer.created_at = Clock::get()?.unix_timestamp;
+    lending_offer.created_at = current_time;
+    lending_offer.expires_at = expires_at;
     lending_offer.bump = ctx.bumps.lending_offer;
 
+    if interest_mode != InterestMode::Variable {
+        ctx.accounts.orderbook.load_mut()?.insert(OrderbookEntry {
+            lending_offer: lending_offer.key(),
+            interest_rate_bps,
+            ltv_bps,
+            remaining_amount: loan_amount,
+            min_fill_amount,
+        });
+    }
+
     Ok(())
 }
\ No newline at end of file
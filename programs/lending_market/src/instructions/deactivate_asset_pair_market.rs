@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct AssetPairMarketStatusChanged {
+    pub asset_pair_market: Pubkey,
+    pub is_active: bool,
+}
+
+#[derive(Accounts)]
+pub struct DeactivateAssetPairMarket<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
+    #[account(
+        mut,
+        seeds = [
+            AssetPairMarket::SEED,
+            asset_pair_market.loan_mint.as_ref(),
+            asset_pair_market.collateral_mint.as_ref()
+        ],
+        bump = asset_pair_market.bump,
+        constraint = asset_pair_market.is_active @ ErrorCode::MarketNotActive,
+    )]
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+}
+
+/// Paused pairs reject create_lending_offer and take_loan.
+/// Open offers can still be cancelled, active loans can still be repaid, called and liquidated.
+pub fn deactivate_asset_pair_market_handler(ctx: Context<DeactivateAssetPairMarket>) -> Result<()> {
+    ctx.accounts.asset_pair_market.is_active = false;
+
+    emit!(AssetPairMarketStatusChanged {
+        asset_pair_market: ctx.accounts.asset_pair_market.key(),
+        is_active: false,
+    });
+
+    Ok(())
+}
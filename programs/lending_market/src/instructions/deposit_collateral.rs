@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::Loan;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct CollateralDeposited {
+    pub loan: Pubkey,
+    pub amount: u64,
+    pub collateral_amount: u64,
+}
+
+// Not checked against the emergency pause: adding collateral only makes a loan safer
+#[derive(Accounts)]
+pub struct DepositCollateral<'info> {
+    pub borrower: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = borrower,
+        constraint = loan.is_active @ ErrorCode::LoanNotActive,
+    )]
+    pub loan: Account<'info, Loan>,
+
+    #[account(
+        mut,
+        seeds = [Loan::COLLATERAL_SEED,loan.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = borrower_collateral_token_account.owner == borrower.key(),
+        constraint = borrower_collateral_token_account.mint == collateral_vault.mint,
+    )]
+    pub borrower_collateral_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn deposit_collateral_handler(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidCollateralAmount);
+
+    // Transfer collateral from borrower to vault
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.borrower_collateral_token_account.to_account_info(),
+        to: ctx.accounts.collateral_vault.to_account_info(),
+        authority: ctx.accounts.borrower.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    let loan = &mut ctx.accounts.loan;
+    loan.collateral_amount = loan
+        .collateral_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::InvalidCollateralAmount)?;
+
+    emit!(CollateralDeposited {
+        loan: loan.key(),
+        amount,
+        collateral_amount: loan.collateral_amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::state::Loan;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct RepaymentDeadlineExtended {
+    pub loan: Pubkey,
+    pub lender: Pubkey,
+    pub borrower: Pubkey,
+    pub old_repayment_deadline: i64,
+    pub new_repayment_deadline: i64,
+}
+
+// Only relaxes the borrower's position, so not checked against the emergency pause
+#[derive(Accounts)]
+pub struct ExtendRepaymentDeadline<'info> {
+    pub lender: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = lender,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        constraint = loan.is_active @ ErrorCode::LoanNotActive,
+    )]
+    pub loan: Account<'info, Loan>,
+
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+}
+
+/// Move a pending deadline later, penalty interest accrued past the old deadline so far stays owed
+pub fn extend_repayment_deadline_handler(
+    ctx: Context<ExtendRepaymentDeadline>,
+    new_repayment_deadline: i64,
+) -> Result<()> {
+    let loan = &mut ctx.accounts.loan;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let old_repayment_deadline = loan.repayment_deadline.ok_or(ErrorCode::RepaymentDeadlineNotSet)?;
+    require!(
+        new_repayment_deadline > old_repayment_deadline && new_repayment_deadline > current_time,
+        ErrorCode::InvalidRepaymentDeadline
+    );
+
+    loan.accrue_interest(current_time, &ctx.accounts.asset_pair_market)?;
+    loan.repayment_deadline = Some(new_repayment_deadline);
+
+    emit!(RepaymentDeadlineExtended {
+        loan: loan.key(),
+        lender: loan.lender,
+        borrower: loan.borrower,
+        old_repayment_deadline,
+        new_repayment_deadline,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer, CloseAccount};
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::instructions::create_borrow_request::BorrowRequest;
+use crate::oracle::OraclePrice;
+use crate::state::{InterestMode, Loan};
+use crate::error::ErrorCode;
+
+#[event]
+pub struct BorrowRequestFilled {
+    pub borrow_request: Pubkey,
+    pub loan: Pubkey,
+    pub lender: Pubkey,
+    pub borrower: Pubkey,
+    pub loan_amount: u64,
+    pub interest_rate_bps: u64,
+}
+
+#[derive(Accounts)]
+pub struct FillBorrowRequest<'info> {
+    #[account(mut)]
+    pub lender: Signer<'info>,
+
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Box<Account<'info, LendingMarket>>,
+
+    #[account(
+        mut,
+        constraint = asset_pair_market.is_active @ ErrorCode::MarketNotActive,
+        constraint = asset_pair_market.loan_mint == loan_mint.key() @ ErrorCode::InvalidAssetPair,
+        constraint = asset_pair_market.collateral_mint == collateral_mint.key() @ ErrorCode::InvalidAssetPair,
+    )]
+    pub asset_pair_market: Box<Account<'info, AssetPairMarket>>,
+
+    #[account(
+        mut,
+        close = borrower,
+        has_one = borrower,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+    )]
+    pub borrow_request: Box<Account<'info, BorrowRequest>>,
+
+    #[account(
+        mut,
+        seeds = [BorrowRequest::COLLATERAL_SEED,borrow_request.key().as_ref()],
+        bump,
+    )]
+    pub request_collateral_escrow: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated against borrow_request, receives the request's rent
+    #[account(mut)]
+    pub borrower: AccountInfo<'info>,
+
+    // Same PDA scheme as take_loan with the request in place of the offer
+    #[account(
+        init,
+        payer = lender,
+        space = 8 + Loan::INIT_SPACE,
+        seeds = [Loan::SEED,borrow_request.key().as_ref(),borrower.key().as_ref()],
+        bump,
+    )]
+    pub loan: Box<Account<'info, Loan>>,
+
+    #[account(
+        init,
+        payer = lender,
+        token::mint = collateral_mint,
+        token::authority = collateral_vault,
+        seeds = [Loan::COLLATERAL_SEED,loan.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated against asset_pair_market
+    pub loan_mint: AccountInfo<'info>,
+
+    /// CHECK: Validated against asset_pair_market
+    pub collateral_mint: AccountInfo<'info>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = loan_price_feed.key() == asset_pair_market.loan_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub loan_price_feed: AccountInfo<'info>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = collateral_price_feed.key() == asset_pair_market.collateral_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub collateral_price_feed: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = lender_token_account.owner == lender.key(),
+        constraint = lender_token_account.mint == loan_mint.key(),
+    )]
+    pub lender_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = borrower_loan_token_account.owner == borrower.key(),
+        constraint = borrower_loan_token_account.mint == loan_mint.key(),
+    )]
+    pub borrower_loan_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated in lending_market
+    #[account(
+        mut,
+        constraint = fee_recipient.key() == lending_market.fee_recipient @ ErrorCode::InvalidFeeRecipient,
+    )]
+    pub fee_recipient: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = fee_recipient_token_account.owner == fee_recipient.key(),
+        constraint = fee_recipient_token_account.mint == loan_mint.key(),
+    )]
+    pub fee_recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// 1. Validate the lender's rate against the request's maximum,
+///    then the escrowed collateral against the loan value at oracle prices and LTV
+/// 2. Transfer loan amount (minus borrower fee) from lender to borrower, fee to fee recipient
+/// 3. Move collateral from the request escrow to the loan's collateral vault, close the escrow
+/// 4. Reimburse the lender the loan and vault rent out of the lamports pre-funded on the request
+/// 5. Initialize loan with simple interest and the request's notice period
+pub fn fill_borrow_request_handler(ctx: Context<FillBorrowRequest>, interest_rate_bps: u64) -> Result<()> {
+    let borrow_request = &ctx.accounts.borrow_request;
+    let lending_market = &ctx.accounts.lending_market;
+    let asset_pair_market = &ctx.accounts.asset_pair_market;
+
+    require!(
+        interest_rate_bps <= borrow_request.max_interest_rate_bps,
+        ErrorCode::InterestRateAboveMax
+    );
+
+    // The pair's liquidation LTV may have been lowered since the request was created
+    require!(borrow_request.ltv_bps < asset_pair_market.liquidation_ltv_bps, ErrorCode::InvalidLTV);
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let loan_amount = borrow_request.loan_amount;
+    let collateral_amount = borrow_request.collateral_amount;
+
+    let loan_price = OraclePrice::load(&ctx.accounts.loan_price_feed, &ctx.accounts.asset_pair_market.loan_feed_id, current_time)?;
+    let collateral_price = OraclePrice::load(&ctx.accounts.collateral_price_feed, &ctx.accounts.asset_pair_market.collateral_feed_id, current_time)?;
+
+    let required_collateral = asset_pair_market.required_collateral(
+        loan_amount,
+        borrow_request.ltv_bps,
+        &loan_price,
+        &collateral_price,
+    )?;
+    require!(
+        collateral_amount >= required_collateral,
+        ErrorCode::InvalidCollateralAmount
+    );
+
+    let loan_value = loan_price.value(loan_amount, asset_pair_market.loan_decimals)?;
+    let collateral_value =
+        collateral_price.value(collateral_amount, asset_pair_market.collateral_decimals)?;
+
+    // Calculate borrower fee (1%)
+    let borrower_fee = loan_amount
+        .checked_mul(lending_market.borrower_fee_bps)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?
+        .checked_div(10000)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+
+    let borrower_receives = loan_amount
+        .checked_sub(borrower_fee)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+
+    // 1. Transfer loan amount (minus fee) from lender to borrower
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.lender_token_account.to_account_info(),
+        to: ctx.accounts.borrower_loan_token_account.to_account_info(),
+        authority: ctx.accounts.lender.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, borrower_receives)?;
+
+    // 2. Transfer fee from lender to fee recipient
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.lender_token_account.to_account_info(),
+        to: ctx.accounts.fee_recipient_token_account.to_account_info(),
+        authority: ctx.accounts.lender.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, borrower_fee)?;
+
+    // Create request escrow authority seeds
+    let request_key = borrow_request.key();
+    let escrow_seeds = &[
+        BorrowRequest::COLLATERAL_SEED,
+        request_key.as_ref(),
+        &[ctx.bumps.request_collateral_escrow],
+    ];
+    let signer_seeds = &[&escrow_seeds[..]];
+
+    // 3. Move collateral from the request escrow to the loan's collateral vault
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.request_collateral_escrow.to_account_info(),
+        to: ctx.accounts.collateral_vault.to_account_info(),
+        authority: ctx.accounts.request_collateral_escrow.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, collateral_amount)?;
+
+    // 4. Close request escrow, rent goes back to the borrower
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.request_collateral_escrow.to_account_info(),
+        destination: ctx.accounts.borrower.to_account_info(),
+        authority: ctx.accounts.request_collateral_escrow.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::close_account(cpi_ctx)?;
+
+    // Reimburse the lender's rent for the loan and vault, repay_loan closes both to the borrower
+    let loan_rent = BorrowRequest::loan_rent(&Rent::get()?);
+    ctx.accounts.borrow_request.sub_lamports(loan_rent)?;
+    ctx.accounts.lender.add_lamports(loan_rent)?;
+
+    // Checkpoint the variable rate before utilization moves
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+    asset_pair_market.update_rate_index(current_time)?;
+    asset_pair_market.total_borrowed = asset_pair_market.total_borrowed.saturating_add(loan_amount);
+    let rate_index = asset_pair_market.rate_index;
+
+    let borrow_request = &ctx.accounts.borrow_request;
+    let loan = &mut ctx.accounts.loan;
+    loan.lending_offer = request_key;
+    loan.asset_pair_market = borrow_request.asset_pair_market;
+    loan.lender = ctx.accounts.lender.key();
+    loan.borrower = borrow_request.borrower;
+    loan.principal_amount = loan_amount;
+    loan.collateral_amount = collateral_amount;
+    loan.interest_rate_bps = interest_rate_bps;
+    loan.ltv_bps = borrow_request.ltv_bps;
+    loan.interest_mode = InterestMode::Simple;
+    loan.penalty_rate_bps = 0;
+    loan.notice_duration = borrow_request.notice_duration;
+    loan.origination_loan_value = u64::try_from(loan_value)
+        .map_err(|_| ErrorCode::PriceCalculationOverflow)?;
+    loan.origination_collateral_value = u64::try_from(collateral_value)
+        .map_err(|_| ErrorCode::PriceCalculationOverflow)?;
+    loan.loan_start_time = current_time;
+    loan.last_interest_update = current_time;
+    loan.accrued_interest = 0;
+    loan.last_rate_index = rate_index;
+    loan.repayment_deadline = None;
+    loan.maturity = None;
+    loan.is_active = true;
+    loan.bump = ctx.bumps.loan;
+
+    emit!(BorrowRequestFilled {
+        borrow_request: request_key,
+        loan: loan.key(),
+        lender: loan.lender,
+        borrower: loan.borrower,
+        loan_amount,
+        interest_rate_bps,
+    });
+
+    Ok(())
+}
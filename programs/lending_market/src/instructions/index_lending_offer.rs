@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::instructions::cancel_lending_offer::LendingOffer;
+use crate::state::{InterestMode, Orderbook, OrderbookEntry};
+use crate::error::ErrorCode;
+
+#[event]
+pub struct LendingOfferIndexed {
+    pub lending_offer: Pubkey,
+    pub indexed_by: Pubkey,
+    pub is_on_book: bool,
+}
+
+/// Permissionless crank to put back an offer that was evicted from a full book,
+/// or never made it on, once it ranks above the worst entry or space frees up
+#[derive(Accounts)]
+pub struct IndexLendingOffer<'info> {
+    pub cranker: Signer<'info>,
+
+    #[account(
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        constraint = lending_offer.is_active @ ErrorCode::OfferNotActive,
+        constraint = !lending_offer.is_expired(Clock::get()?.unix_timestamp) @ ErrorCode::OfferExpired,
+        constraint = lending_offer.interest_mode != InterestMode::Variable @ ErrorCode::VariableOfferNotIndexed,
+    )]
+    pub lending_offer: Account<'info, LendingOffer>,
+
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+
+    #[account(
+        mut,
+        seeds = [Orderbook::SEED,asset_pair_market.key().as_ref()],
+        bump,
+    )]
+    pub orderbook: AccountLoader<'info, Orderbook>,
+}
+
+/// No-op for an offer already on the book with the same terms
+pub fn index_lending_offer_handler(ctx: Context<IndexLendingOffer>) -> Result<()> {
+    let lending_offer = &ctx.accounts.lending_offer;
+    let mut orderbook = ctx.accounts.orderbook.load_mut()?;
+
+    orderbook.update_entry(OrderbookEntry {
+        lending_offer: lending_offer.key(),
+        interest_rate_bps: lending_offer.interest_rate_bps,
+        ltv_bps: lending_offer.ltv_bps,
+        remaining_amount: lending_offer.remaining_amount,
+        min_fill_amount: lending_offer.min_fill_amount,
+    });
+
+    emit!(LendingOfferIndexed {
+        lending_offer: lending_offer.key(),
+        indexed_by: ctx.accounts.cranker.key(),
+        is_on_book: orderbook.contains(&lending_offer.key()),
+    });
+
+    Ok(())
+}
@@ -6,15 +6,25 @@ use crate::error::ErrorCode;
 #[derive(InitSpace)]
 pub struct LendingMarket {
     pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,  // set by propose_admin, cleared by accept_admin
     pub fee_recipient: Pubkey,
     pub lender_fee_bps: u64,    // 200 = 2% fee when lender gets repaid
     pub borrower_fee_bps: u64,  // 100 = 1% fee when borrower takes loan
+    pub guardian: Pubkey,       // can trip the emergency pause alongside the admin
+    pub is_paused: bool,        // emergency pause: blocks new offers, loans, calls and liquidations
+    pub min_notice_duration: i64,  // seconds, bounds the repayment notice lenders set on offers
+    pub max_notice_duration: i64,
     pub bump: u8,
 }
 
 
 impl LendingMarket {
     pub const SEED: &'static [u8] = b"lending_market";
+    pub const MAX_LENDER_FEE_BPS: u64 = 1000;    // 10%
+    pub const MAX_BORROWER_FEE_BPS: u64 = 500;   // 5%
+    pub const DEFAULT_MIN_NOTICE_DURATION: i64 = 24 * 60 * 60;       // 1 day
+    pub const DEFAULT_MAX_NOTICE_DURATION: i64 = 30 * 24 * 60 * 60;  // 30 days
+    pub const MAX_NOTICE_DURATION: i64 = 365 * 24 * 60 * 60;         // upper limit for max_notice_duration
 }
 
 #[derive(Accounts)]
@@ -40,14 +50,19 @@ pub fn init_lending_market_handler(
     lender_fee_bps: u64,
     borrower_fee_bps: u64,
 ) -> Result<()> {
-    require!(lender_fee_bps <= 10000, ErrorCode::FeeTooHigh);
-    require!(borrower_fee_bps <= 10000, ErrorCode::FeeTooHigh);
+    require!(lender_fee_bps <= LendingMarket::MAX_LENDER_FEE_BPS, ErrorCode::FeeTooHigh);
+    require!(borrower_fee_bps <= LendingMarket::MAX_BORROWER_FEE_BPS, ErrorCode::FeeTooHigh);
 
     let lending_market = &mut ctx.accounts.lending_market;
     lending_market.admin = ctx.accounts.admin.key();
+    lending_market.pending_admin = None;
     lending_market.fee_recipient = fee_recipient;
     lending_market.lender_fee_bps = lender_fee_bps;
     lending_market.borrower_fee_bps = borrower_fee_bps;
+    lending_market.guardian = ctx.accounts.admin.key();
+    lending_market.is_paused = false;
+    lending_market.min_notice_duration = LendingMarket::DEFAULT_MIN_NOTICE_DURATION;
+    lending_market.max_notice_duration = LendingMarket::DEFAULT_MAX_NOTICE_DURATION;
     lending_market.bump = ctx.bumps.lending_market;
 
     Ok(())
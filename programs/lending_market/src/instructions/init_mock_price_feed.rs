@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::error::ErrorCode;
+
+/// Program-owned price account for a mint, readable by the oracle layer like a Pyth feed
+#[account]
+#[derive(InitSpace)]
+pub struct MockPriceFeed {
+    pub mint: Pubkey,
+    pub price: i64,         // price of one whole token = price * 10^expo
+    pub expo: i32,
+    pub publish_time: i64,
+    pub bump: u8,
+}
+
+/// seeds = [MockPriceFeed::SEED, mint.key().as_ref()]
+impl MockPriceFeed {
+    pub const SEED: &'static [u8] = b"mock_price";
+}
+
+#[derive(Accounts)]
+pub struct InitMockPriceFeed<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + MockPriceFeed::INIT_SPACE,
+        seeds = [MockPriceFeed::SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub mock_price_feed: Account<'info, MockPriceFeed>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_mock_price_feed_handler(
+    ctx: Context<InitMockPriceFeed>,
+    price: i64,
+    expo: i32,
+) -> Result<()> {
+    require!(price > 0, ErrorCode::InvalidPrice);
+
+    let mock_price_feed = &mut ctx.accounts.mock_price_feed;
+    mock_price_feed.mint = ctx.accounts.mint.key();
+    mock_price_feed.price = price;
+    mock_price_feed.expo = expo;
+    mock_price_feed.publish_time = Clock::get()?.unix_timestamp;
+    mock_price_feed.bump = ctx.bumps.mock_price_feed;
+
+    Ok(())
+}
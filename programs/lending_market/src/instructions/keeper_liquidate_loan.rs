@@ -0,0 +1,235 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer, CloseAccount};
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::oracle::OraclePrice;
+use crate::state::Loan;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct LoanLiquidatedByKeeper {
+    pub loan: Pubkey,
+    pub keeper: Pubkey,
+    pub debt: u64,
+    pub lender_fee: u64,
+    pub collateral_to_keeper: u64,
+    pub collateral_returned: u64,
+}
+
+#[derive(Accounts)]
+pub struct KeeperLiquidateLoan<'info> {
+    pub keeper: Signer<'info>,
+
+    // Liquidations are oracle driven, halt them with the rest of the protocol
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Box<Account<'info, LendingMarket>>,
+
+    // Rent goes back to the borrower who paid it, the lender is repaid in full
+    #[account(
+        mut,
+        close = borrower,
+        has_one = lender,
+        has_one = borrower,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        constraint = loan.is_active @ ErrorCode::LoanNotActive,
+    )]
+    pub loan: Box<Account<'info, Loan>>,
+
+    #[account(mut)]
+    pub asset_pair_market: Box<Account<'info, AssetPairMarket>>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = loan_price_feed.key() == asset_pair_market.loan_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub loan_price_feed: AccountInfo<'info>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = collateral_price_feed.key() == asset_pair_market.collateral_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub collateral_price_feed: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [Loan::COLLATERAL_SEED,loan.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = keeper_loan_token_account.owner == keeper.key(),
+    )]
+    pub keeper_loan_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = keeper_collateral_token_account.owner == keeper.key(),
+        constraint = keeper_collateral_token_account.mint == collateral_vault.mint,
+    )]
+    pub keeper_collateral_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated as lender from loan
+    pub lender: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = lender_token_account.owner == lender.key(),
+        constraint = lender_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub lender_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated as borrower from loan, receives the loan and vault rent
+    #[account(mut)]
+    pub borrower: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = borrower_collateral_token_account.owner == borrower.key(),
+        constraint = borrower_collateral_token_account.mint == collateral_vault.mint,
+    )]
+    pub borrower_collateral_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated in lending_market
+    #[account(
+        constraint = fee_recipient.key() == lending_market.fee_recipient @ ErrorCode::InvalidFeeRecipient,
+    )]
+    pub fee_recipient: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = fee_recipient_token_account.owner == fee_recipient.key(),
+    )]
+    pub fee_recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Permissionless liquidation of a loan whose oracle-priced LTV exceeds the pair's liquidation_ltv_bps
+/// 1. Keeper repays the debt to the lender (lender fee goes to fee recipient, as in repay_loan)
+/// 2. Keeper receives collateral worth the debt plus the pair's liquidation bonus
+/// 3. Remaining collateral is returned to the borrower
+///
+/// Deadline-based liquidation stays with the lender through liquidate_loan.
+pub fn keeper_liquidate_loan_handler(ctx: Context<KeeperLiquidateLoan>) -> Result<()> {
+    let loan = &ctx.accounts.loan;
+    let asset_pair_market = &ctx.accounts.asset_pair_market;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let loan_price = OraclePrice::load(&ctx.accounts.loan_price_feed, &ctx.accounts.asset_pair_market.loan_feed_id, current_time)?;
+    let collateral_price = OraclePrice::load(&ctx.accounts.collateral_price_feed, &ctx.accounts.asset_pair_market.collateral_feed_id, current_time)?;
+
+    require!(
+        loan.is_undercollateralized(current_time, asset_pair_market, &loan_price, &collateral_price)?,
+        ErrorCode::CannotLiquidateHealthyLoan
+    );
+
+    let debt = loan.calculate_repayment_amount(current_time, asset_pair_market)?;
+
+    // Calculate lender fee (2%)
+    let lender_fee = debt
+        .checked_mul(ctx.accounts.lending_market.lender_fee_bps)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?
+        .checked_div(10000)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+
+    let lender_receives = debt
+        .checked_sub(lender_fee)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+
+    let collateral_owed = asset_pair_market.collateral_for_debt(
+        debt,
+        asset_pair_market.liquidation_bonus_bps,
+        &loan_price,
+        &collateral_price,
+    )?;
+    let collateral_to_keeper = collateral_owed.min(ctx.accounts.collateral_vault.amount);
+    let collateral_returned = ctx.accounts.collateral_vault.amount - collateral_to_keeper;
+
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+    asset_pair_market.update_rate_index(current_time)?;
+    asset_pair_market.total_borrowed = asset_pair_market.total_borrowed.saturating_sub(loan.principal_amount);
+
+    // 1. Transfer debt (minus fee) from keeper to lender
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.keeper_loan_token_account.to_account_info(),
+        to: ctx.accounts.lender_token_account.to_account_info(),
+        authority: ctx.accounts.keeper.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, lender_receives)?;
+
+    // 2. Transfer fee from keeper to fee recipient
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.keeper_loan_token_account.to_account_info(),
+        to: ctx.accounts.fee_recipient_token_account.to_account_info(),
+        authority: ctx.accounts.keeper.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, lender_fee)?;
+
+    let loan_key = loan.key();
+
+    // Create collateral vault authority seeds
+    let collateral_seeds = &[
+        Loan::COLLATERAL_SEED,
+        loan_key.as_ref(),
+        &[ctx.bumps.collateral_vault],
+    ];
+    let signer_seeds = &[&collateral_seeds[..]];
+
+    // 3. Transfer discounted collateral to keeper
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.collateral_vault.to_account_info(),
+        to: ctx.accounts.keeper_collateral_token_account.to_account_info(),
+        authority: ctx.accounts.collateral_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, collateral_to_keeper)?;
+
+    // 4. Return the surplus to borrower
+    if collateral_returned > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.collateral_vault.to_account_info(),
+            to: ctx.accounts.borrower_collateral_token_account.to_account_info(),
+            authority: ctx.accounts.collateral_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, collateral_returned)?;
+    }
+
+    // Close collateral vault account
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.collateral_vault.to_account_info(),
+        destination: ctx.accounts.borrower.to_account_info(),
+        authority: ctx.accounts.collateral_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::close_account(cpi_ctx)?;
+
+    emit!(LoanLiquidatedByKeeper {
+        loan: loan_key,
+        keeper: ctx.accounts.keeper.key(),
+        debt,
+        lender_fee,
+        collateral_to_keeper,
+        collateral_returned,
+    });
+
+    Ok(())
+}
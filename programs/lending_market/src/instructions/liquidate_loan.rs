@@ -1,20 +1,56 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer, CloseAccount};
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::oracle::OraclePrice;
 use crate::state::Loan;
 use crate::error::ErrorCode;
 
+#[event]
+pub struct LoanLiquidated {
+    pub loan: Pubkey,
+    pub lender: Pubkey,
+    pub borrower: Pubkey,
+    pub debt: u64,
+    pub collateral_seized: u64,
+    pub collateral_returned: u64,
+}
+
 #[derive(Accounts)]
 pub struct LiquidateLoan<'info> {
+    #[account(mut)]
     pub lender: Signer<'info>,
 
+    // Liquidations are oracle driven, halt them with the rest of the protocol
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
     #[account(
         mut,
         close = lender,
         has_one = lender,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
         constraint = loan.is_active @ ErrorCode::LoanNotActive,
     )]
     pub loan: Account<'info, Loan>,
 
+    #[account(mut)]
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = loan_price_feed.key() == asset_pair_market.loan_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub loan_price_feed: AccountInfo<'info>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = collateral_price_feed.key() == asset_pair_market.collateral_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub collateral_price_feed: AccountInfo<'info>,
+
     #[account(
         mut,
         seeds = [Loan::COLLATERAL_SEED,loan.key().as_ref()],
@@ -28,19 +64,49 @@ pub struct LiquidateLoan<'info> {
     )]
     pub lender_token_account: Account<'info, TokenAccount>,
 
+    #[account(
+        mut,
+        constraint = borrower_collateral_token_account.owner == loan.borrower,
+        constraint = borrower_collateral_token_account.mint == collateral_vault.mint,
+    )]
+    pub borrower_collateral_token_account: Account<'info, TokenAccount>,
+
     pub token_program: Program<'info, Token>,
 }
 
-pub fn liquidate_loan_handler(ctx: Context<LiquidateLoan>, current_ltv_bps: u64) -> Result<()> {
+pub fn liquidate_loan_handler(ctx: Context<LiquidateLoan>) -> Result<()> {
     let loan = &ctx.accounts.loan;
     let current_time = Clock::get()?.unix_timestamp;
 
+    let loan_price = OraclePrice::load(&ctx.accounts.loan_price_feed, &ctx.accounts.asset_pair_market.loan_feed_id, current_time)?;
+    let collateral_price = OraclePrice::load(&ctx.accounts.collateral_price_feed, &ctx.accounts.asset_pair_market.collateral_feed_id, current_time)?;
+
     // Verify loan can be liquidated
     require!(
-        loan.can_liquidate(current_time, current_ltv_bps),
+        loan.can_liquidate(
+            current_time,
+            &ctx.accounts.asset_pair_market,
+            &loan_price,
+            &collateral_price,
+        )?,
         ErrorCode::CannotLiquidateHealthyLoan
     );
 
+    // Lender seizes collateral worth the debt plus the pair's liquidation penalty
+    let debt = loan.calculate_repayment_amount(current_time, &ctx.accounts.asset_pair_market)?;
+    let collateral_owed = ctx.accounts.asset_pair_market.collateral_for_debt(
+        debt,
+        ctx.accounts.asset_pair_market.liquidation_penalty_bps,
+        &loan_price,
+        &collateral_price,
+    )?;
+    let collateral_seized = collateral_owed.min(ctx.accounts.collateral_vault.amount);
+    let collateral_returned = ctx.accounts.collateral_vault.amount - collateral_seized;
+
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+    asset_pair_market.update_rate_index(current_time)?;
+    asset_pair_market.total_borrowed = asset_pair_market.total_borrowed.saturating_sub(loan.principal_amount);
+
     let loan_key = loan.key();
 
     // Create collateral vault authority seeds
@@ -51,7 +117,7 @@ pub fn liquidate_loan_handler(ctx: Context<LiquidateLoan>, current_ltv_bps: u64)
     ];
     let signer_seeds = &[&collateral_seeds[..]];
 
-    // Transfer all collateral to lender
+    // 1. Transfer seized collateral to lender
     let cpi_accounts = Transfer {
         from: ctx.accounts.collateral_vault.to_account_info(),
         to: ctx.accounts.lender_token_account.to_account_info(),
@@ -62,7 +128,22 @@ pub fn liquidate_loan_handler(ctx: Context<LiquidateLoan>, current_ltv_bps: u64)
         cpi_accounts,
         signer_seeds,
     );
-    token::transfer(cpi_ctx, ctx.accounts.collateral_vault.amount)?;
+    token::transfer(cpi_ctx, collateral_seized)?;
+
+    // 2. Return the surplus to borrower
+    if collateral_returned > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.collateral_vault.to_account_info(),
+            to: ctx.accounts.borrower_collateral_token_account.to_account_info(),
+            authority: ctx.accounts.collateral_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, collateral_returned)?;
+    }
 
     // Close collateral vault account
     let cpi_accounts = CloseAccount {
@@ -77,5 +158,14 @@ pub fn liquidate_loan_handler(ctx: Context<LiquidateLoan>, current_ltv_bps: u64)
     );
     token::close_account(cpi_ctx)?;
 
+    emit!(LoanLiquidated {
+        loan: loan_key,
+        lender: loan.lender,
+        borrower: loan.borrower,
+        debt,
+        collateral_seized,
+        collateral_returned,
+    });
+
     Ok(())
 }
\ No newline at end of file
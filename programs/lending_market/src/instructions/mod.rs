@@ -1,17 +1,77 @@
 pub mod init_lending_market;
+pub mod update_lending_market;
+pub mod propose_admin;
+pub mod accept_admin;
+pub mod set_protocol_paused;
 pub mod create_asset_pair_market;
+pub mod deactivate_asset_pair_market;
+pub mod reactivate_asset_pair_market;
+pub mod update_asset_pair_market;
+#[cfg(feature = "mock-oracle")]
+pub mod init_mock_price_feed;
+#[cfg(feature = "mock-oracle")]
+pub mod set_mock_price;
 pub mod create_lending_offer;
 pub mod cancel_lending_offer;
+pub mod close_expired_offer;
+pub mod index_lending_offer;
+pub mod update_lending_offer;
+pub mod top_up_offer;
+pub mod withdraw_from_offer;
 pub mod take_loan;
+pub mod borrow_market;
 pub mod repay_loan;
+pub mod partial_repay;
+pub mod refinance_loan;
+pub mod create_borrow_request;
+pub mod cancel_borrow_request;
+pub mod fill_borrow_request;
+pub mod deposit_collateral;
+pub mod withdraw_collateral;
 pub mod request_repayment;
+pub mod cancel_repayment_request;
+pub mod extend_repayment_deadline;
 pub mod liquidate_loan;
+pub mod keeper_liquidate_loan;
+pub mod start_collateral_auction;
+pub mod bid_collateral_auction;
+pub mod settle_collateral_auction;
 
 pub use init_lending_market::*;
+pub use update_lending_market::*;
+pub use propose_admin::*;
+pub use accept_admin::*;
+pub use set_protocol_paused::*;
 pub use create_asset_pair_market::*;
+pub use deactivate_asset_pair_market::*;
+pub use reactivate_asset_pair_market::*;
+pub use update_asset_pair_market::*;
+#[cfg(feature = "mock-oracle")]
+pub use init_mock_price_feed::*;
+#[cfg(feature = "mock-oracle")]
+pub use set_mock_price::*;
 pub use create_lending_offer::*;
 pub use cancel_lending_offer::*;
+pub use close_expired_offer::*;
+pub use index_lending_offer::*;
+pub use update_lending_offer::*;
+pub use top_up_offer::*;
+pub use withdraw_from_offer::*;
 pub use take_loan::*;
+pub use borrow_market::*;
 pub use repay_loan::*;
+pub use partial_repay::*;
+pub use refinance_loan::*;
+pub use create_borrow_request::*;
+pub use cancel_borrow_request::*;
+pub use fill_borrow_request::*;
+pub use deposit_collateral::*;
+pub use withdraw_collateral::*;
 pub use request_repayment::*;
-pub use liquidate_loan::*;
\ No newline at end of file
+pub use cancel_repayment_request::*;
+pub use extend_repayment_deadline::*;
+pub use liquidate_loan::*;
+pub use keeper_liquidate_loan::*;
+pub use start_collateral_auction::*;
+pub use bid_collateral_auction::*;
+pub use settle_collateral_auction::*;
\ No newline at end of file
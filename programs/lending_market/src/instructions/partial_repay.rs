@@ -0,0 +1,220 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::oracle::OraclePrice;
+use crate::state::Loan;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct LoanPartiallyRepaid {
+    pub loan: Pubkey,
+    pub interest_paid: u64,
+    pub principal_paid: u64,
+    pub lender_fee: u64,
+    pub collateral_released: u64,
+    pub remaining_principal: u64,
+}
+
+#[derive(Accounts)]
+pub struct PartialRepay<'info> {
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+
+    // Repaying is allowed while paused, releasing collateral is not (checked in handler)
+    pub lending_market: Account<'info, LendingMarket>,
+
+    #[account(
+        mut,
+        has_one = borrower,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        constraint = loan.is_active @ ErrorCode::LoanNotActive,
+    )]
+    pub loan: Account<'info, Loan>,
+
+    #[account(mut)]
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = loan_price_feed.key() == asset_pair_market.loan_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub loan_price_feed: AccountInfo<'info>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = collateral_price_feed.key() == asset_pair_market.collateral_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub collateral_price_feed: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [Loan::COLLATERAL_SEED,loan.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = borrower_loan_token_account.owner == borrower.key(),
+        constraint = borrower_loan_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub borrower_loan_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = borrower_collateral_token_account.owner == borrower.key(),
+    )]
+    pub borrower_collateral_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Validated as lender from loan
+    #[account(
+        constraint = lender.key() == loan.lender,
+    )]
+    pub lender: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = lender_token_account.owner == lender.key(),
+        constraint = lender_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub lender_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Validated in lending_market
+    #[account(
+        constraint = fee_recipient.key() == lending_market.fee_recipient @ ErrorCode::InvalidFeeRecipient,
+    )]
+    pub fee_recipient: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = fee_recipient_token_account.owner == fee_recipient.key(),
+        constraint = fee_recipient_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub fee_recipient_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// 1. Checkpoint accrued interest
+/// 2. Apply payment to interest first, then principal
+/// 3. Charge lender fee (2%) on the amount paid
+/// 4. Optionally release collateral pro-rata to principal paid, capped so LTV stays within ltv_bps
+pub fn partial_repay_handler(
+    ctx: Context<PartialRepay>,
+    amount: u64,
+    release_collateral: bool,
+) -> Result<()> {
+    let lending_market = &ctx.accounts.lending_market;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(
+        !release_collateral || !lending_market.is_paused,
+        ErrorCode::ProtocolPaused
+    );
+
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+    asset_pair_market.update_rate_index(current_time)?;
+
+    let loan = &mut ctx.accounts.loan;
+    loan.accrue_interest(current_time, asset_pair_market)?;
+
+    // Full repayment closes the loan through repay_loan
+    let total_debt = loan.calculate_repayment_amount(current_time, asset_pair_market)?;
+    require!(amount > 0 && amount < total_debt, ErrorCode::InvalidLoanAmount);
+
+    let interest_paid = amount.min(loan.accrued_interest);
+    let principal_paid = amount - interest_paid;
+
+    // Calculate lender fee (2%) on the amount paid
+    let lender_fee = amount
+        .checked_mul(lending_market.lender_fee_bps)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?
+        .checked_div(10000)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+
+    let lender_receives = amount
+        .checked_sub(lender_fee)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+
+    let principal_before = loan.principal_amount;
+    loan.accrued_interest -= interest_paid;
+    loan.principal_amount -= principal_paid;
+    asset_pair_market.total_borrowed = asset_pair_market.total_borrowed.saturating_sub(principal_paid);
+
+    // Collateral released pro-rata to principal paid, never below what ltv_bps requires
+    let mut collateral_released = 0;
+    if release_collateral && principal_paid > 0 {
+        let loan_price = OraclePrice::load(&ctx.accounts.loan_price_feed, &asset_pair_market.loan_feed_id, current_time)?;
+        let collateral_price = OraclePrice::load(&ctx.accounts.collateral_price_feed, &asset_pair_market.collateral_feed_id, current_time)?;
+
+        let pro_rata = (loan.collateral_amount as u128)
+            .checked_mul(principal_paid as u128)
+            .ok_or(ErrorCode::InterestCalculationOverflow)?
+            .checked_div(principal_before as u128)
+            .ok_or(ErrorCode::InterestCalculationOverflow)? as u64;
+
+        let remaining_debt = loan.calculate_repayment_amount(current_time, asset_pair_market)?;
+        let required_collateral = asset_pair_market.required_collateral(
+            remaining_debt,
+            loan.ltv_bps,
+            &loan_price,
+            &collateral_price,
+        )?;
+
+        collateral_released = pro_rata.min(loan.collateral_amount.saturating_sub(required_collateral));
+        loan.collateral_amount -= collateral_released;
+    }
+
+    // 1. Transfer payment (minus fee) from borrower to lender
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.borrower_loan_token_account.to_account_info(),
+        to: ctx.accounts.lender_token_account.to_account_info(),
+        authority: ctx.accounts.borrower.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, lender_receives)?;
+
+    // 2. Transfer fee from borrower to fee recipient
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.borrower_loan_token_account.to_account_info(),
+        to: ctx.accounts.fee_recipient_token_account.to_account_info(),
+        authority: ctx.accounts.borrower.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, lender_fee)?;
+
+    // 3. Release collateral from vault to borrower
+    if collateral_released > 0 {
+        let loan_key = ctx.accounts.loan.key();
+        let collateral_seeds = &[
+            Loan::COLLATERAL_SEED,
+            loan_key.as_ref(),
+            &[ctx.bumps.collateral_vault],
+        ];
+        let signer_seeds = &[&collateral_seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.collateral_vault.to_account_info(),
+            to: ctx.accounts.borrower_collateral_token_account.to_account_info(),
+            authority: ctx.accounts.collateral_vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, collateral_released)?;
+    }
+
+    emit!(LoanPartiallyRepaid {
+        loan: ctx.accounts.loan.key(),
+        interest_paid,
+        principal_paid,
+        lender_fee,
+        collateral_released,
+        remaining_principal: ctx.accounts.loan.principal_amount,
+    });
+
+    Ok(())
+}
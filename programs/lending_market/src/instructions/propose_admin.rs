@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct AdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+        seeds = [LendingMarket::SEED],
+        bump = lending_market.bump,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+}
+
+/// First step of the admin transfer, `None` cancels a pending proposal
+pub fn propose_admin_handler(ctx: Context<ProposeAdmin>, new_admin: Option<Pubkey>) -> Result<()> {
+    let lending_market = &mut ctx.accounts.lending_market;
+
+    if let Some(new_admin) = new_admin {
+        require!(
+            new_admin != Pubkey::default() && new_admin != lending_market.admin,
+            ErrorCode::InvalidAdmin
+        );
+    }
+
+    lending_market.pending_admin = new_admin;
+
+    emit!(AdminProposed {
+        admin: lending_market.admin,
+        pending_admin: new_admin,
+    });
+
+    Ok(())
+}
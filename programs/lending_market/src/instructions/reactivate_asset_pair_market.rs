@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::instructions::deactivate_asset_pair_market::AssetPairMarketStatusChanged;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ReactivateAssetPairMarket<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
+    #[account(
+        mut,
+        seeds = [
+            AssetPairMarket::SEED,
+            asset_pair_market.loan_mint.as_ref(),
+            asset_pair_market.collateral_mint.as_ref()
+        ],
+        bump = asset_pair_market.bump,
+        constraint = !asset_pair_market.is_active @ ErrorCode::MarketAlreadyActive,
+    )]
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+}
+
+pub fn reactivate_asset_pair_market_handler(ctx: Context<ReactivateAssetPairMarket>) -> Result<()> {
+    ctx.accounts.asset_pair_market.is_active = true;
+
+    emit!(AssetPairMarketStatusChanged {
+        asset_pair_market: ctx.accounts.asset_pair_market.key(),
+        is_active: true,
+    });
+
+    Ok(())
+}
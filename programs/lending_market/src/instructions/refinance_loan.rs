@@ -0,0 +1,364 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer, CloseAccount};
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::instructions::cancel_lending_offer::LendingOffer;
+use crate::oracle::OraclePrice;
+use crate::state::{Loan, Orderbook};
+use crate::error::ErrorCode;
+
+#[event]
+pub struct LoanRefinanced {
+    pub old_loan: Pubkey,
+    pub new_loan: Pubkey,
+    pub borrower: Pubkey,
+    pub old_lender: Pubkey,
+    pub new_lender: Pubkey,
+    pub debt_repaid: u64,
+    pub lender_fee: u64,
+    pub borrower_fee: u64,
+    pub collateral_amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct RefinanceLoan<'info> {
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Box<Account<'info, LendingMarket>>,
+
+    #[account(
+        mut,
+        constraint = asset_pair_market.is_active @ ErrorCode::MarketNotActive,
+    )]
+    pub asset_pair_market: Box<Account<'info, AssetPairMarket>>,
+
+    #[account(
+        mut,
+        seeds = [Orderbook::SEED,asset_pair_market.key().as_ref()],
+        bump,
+    )]
+    pub orderbook: AccountLoader<'info, Orderbook>,
+
+    #[account(
+        mut,
+        close = borrower,
+        has_one = borrower,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        constraint = old_loan.is_active @ ErrorCode::LoanNotActive,
+    )]
+    pub old_loan: Box<Account<'info, Loan>>,
+
+    #[account(
+        mut,
+        seeds = [Loan::COLLATERAL_SEED,old_loan.key().as_ref()],
+        bump,
+    )]
+    pub old_collateral_vault: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated as lender from old loan
+    #[account(
+        constraint = old_lender.key() == old_loan.lender,
+    )]
+    pub old_lender: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = old_lender_token_account.owner == old_lender.key(),
+        constraint = old_lender_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub old_lender_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        constraint = lending_offer.is_active @ ErrorCode::OfferNotActive,
+    )]
+    pub lending_offer: Box<Account<'info, LendingOffer>>,
+
+    #[account(
+        mut,
+        seeds = [LendingOffer::ESCROW_SEED,lending_offer.key().as_ref()],
+        bump,
+    )]
+    pub escrow: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = borrower,
+        space = 8 + Loan::INIT_SPACE,
+        seeds = [Loan::SEED,lending_offer.key().as_ref(),borrower.key().as_ref()],
+        bump,
+    )]
+    pub new_loan: Box<Account<'info, Loan>>,
+
+    #[account(
+        init,
+        payer = borrower,
+        token::mint = collateral_mint,
+        token::authority = new_collateral_vault,
+        seeds = [Loan::COLLATERAL_SEED,new_loan.key().as_ref()],
+        bump,
+    )]
+    pub new_collateral_vault: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated against asset_pair_market
+    #[account(
+        constraint = collateral_mint.key() == asset_pair_market.collateral_mint @ ErrorCode::InvalidAssetPair,
+    )]
+    pub collateral_mint: AccountInfo<'info>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = loan_price_feed.key() == asset_pair_market.loan_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub loan_price_feed: AccountInfo<'info>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = collateral_price_feed.key() == asset_pair_market.collateral_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub collateral_price_feed: AccountInfo<'info>,
+
+    // Pays the borrower fee on the new loan
+    #[account(
+        mut,
+        constraint = borrower_loan_token_account.owner == borrower.key(),
+        constraint = borrower_loan_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub borrower_loan_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated in lending_market
+    #[account(
+        mut,
+        constraint = fee_recipient.key() == lending_market.fee_recipient @ ErrorCode::InvalidFeeRecipient,
+    )]
+    pub fee_recipient: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = fee_recipient_token_account.owner == fee_recipient.key(),
+        constraint = fee_recipient_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub fee_recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Move a loan to another offer on the same pair in one transaction
+/// 1. New principal = old principal + interest, drawn from the new offer's escrow
+/// 2. Escrow repays the old lender (lender fee goes to fee recipient, as in repay_loan)
+/// 3. Borrower pays the borrower fee on the new principal, as in take_loan
+/// 4. Collateral moves to the new vault and must cover the new offer's LTV at oracle prices
+/// 5. Old loan and vault are closed to the borrower
+pub fn refinance_loan_handler(ctx: Context<RefinanceLoan>) -> Result<()> {
+    let lending_market = &ctx.accounts.lending_market;
+    let lending_offer = &ctx.accounts.lending_offer;
+    let old_loan = &ctx.accounts.old_loan;
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(!lending_offer.is_expired(current_time), ErrorCode::OfferExpired);
+
+    let debt = old_loan.calculate_repayment_amount(current_time, &ctx.accounts.asset_pair_market)?;
+
+    require!(
+        debt <= lending_offer.remaining_amount,
+        ErrorCode::InsufficientFunds
+    );
+    require!(
+        debt >= lending_offer.min_fill_amount
+            || debt == lending_offer.remaining_amount,
+        ErrorCode::FillBelowMinimum
+    );
+
+    // Collateral carried over must satisfy the new offer's LTV
+    let loan_price = OraclePrice::load(&ctx.accounts.loan_price_feed, &ctx.accounts.asset_pair_market.loan_feed_id, current_time)?;
+    let collateral_price = OraclePrice::load(&ctx.accounts.collateral_price_feed, &ctx.accounts.asset_pair_market.collateral_feed_id, current_time)?;
+    let asset_pair_market = &ctx.accounts.asset_pair_market;
+    // The pair's liquidation LTV may have been lowered since the offer was created
+    require!(lending_offer.ltv_bps < asset_pair_market.liquidation_ltv_bps, ErrorCode::InvalidLTV);
+
+    let collateral_amount = ctx.accounts.old_collateral_vault.amount;
+    let required_collateral = asset_pair_market.required_collateral(
+        debt,
+        lending_offer.ltv_bps,
+        &loan_price,
+        &collateral_price,
+    )?;
+    require!(
+        collateral_amount >= required_collateral,
+        ErrorCode::InvalidCollateralAmount
+    );
+
+    let loan_value = loan_price.value(debt, asset_pair_market.loan_decimals)?;
+    let collateral_value =
+        collateral_price.value(collateral_amount, asset_pair_market.collateral_decimals)?;
+
+    // Calculate lender fee (2%) on the old loan and borrower fee (1%) on the new one
+    let lender_fee = debt
+        .checked_mul(lending_market.lender_fee_bps)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?
+        .checked_div(10000)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+
+    let lender_receives = debt
+        .checked_sub(lender_fee)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+
+    let borrower_fee = debt
+        .checked_mul(lending_market.borrower_fee_bps)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?
+        .checked_div(10000)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+
+    // Create escrow authority seeds
+    let lending_offer_key = lending_offer.key();
+    let escrow_seeds = &[
+        LendingOffer::ESCROW_SEED,
+        lending_offer_key.as_ref(),
+        &[ctx.bumps.escrow],
+    ];
+    let escrow_signer_seeds = &[&escrow_seeds[..]];
+
+    // 1. Transfer repayment (minus fee) from escrow to old lender
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.escrow.to_account_info(),
+        to: ctx.accounts.old_lender_token_account.to_account_info(),
+        authority: ctx.accounts.escrow.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        escrow_signer_seeds,
+    );
+    token::transfer(cpi_ctx, lender_receives)?;
+
+    // 2. Transfer lender fee from escrow to fee recipient
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.escrow.to_account_info(),
+        to: ctx.accounts.fee_recipient_token_account.to_account_info(),
+        authority: ctx.accounts.escrow.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        escrow_signer_seeds,
+    );
+    token::transfer(cpi_ctx, lender_fee)?;
+
+    // 3. Transfer borrower fee from borrower to fee recipient
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.borrower_loan_token_account.to_account_info(),
+        to: ctx.accounts.fee_recipient_token_account.to_account_info(),
+        authority: ctx.accounts.borrower.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, borrower_fee)?;
+
+    // Create old collateral vault authority seeds
+    let old_loan_key = old_loan.key();
+    let collateral_seeds = &[
+        Loan::COLLATERAL_SEED,
+        old_loan_key.as_ref(),
+        &[ctx.bumps.old_collateral_vault],
+    ];
+    let collateral_signer_seeds = &[&collateral_seeds[..]];
+
+    // 4. Move collateral to the new vault
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.old_collateral_vault.to_account_info(),
+        to: ctx.accounts.new_collateral_vault.to_account_info(),
+        authority: ctx.accounts.old_collateral_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        collateral_signer_seeds,
+    );
+    token::transfer(cpi_ctx, collateral_amount)?;
+
+    // Close old collateral vault account
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.old_collateral_vault.to_account_info(),
+        destination: ctx.accounts.borrower.to_account_info(),
+        authority: ctx.accounts.old_collateral_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        collateral_signer_seeds,
+    );
+    token::close_account(cpi_ctx)?;
+
+    // Old principal leaves the pair's utilization, new principal enters it
+    let old_principal = old_loan.principal_amount;
+    let old_lender = old_loan.lender;
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+    asset_pair_market.update_rate_index(current_time)?;
+    asset_pair_market.total_available = asset_pair_market.total_available.saturating_sub(debt);
+    asset_pair_market.total_borrowed = asset_pair_market
+        .total_borrowed
+        .saturating_sub(old_principal)
+        .saturating_add(debt);
+    let rate_index = asset_pair_market.rate_index;
+
+    {
+        // Initialize new loan
+        let new_loan = &mut ctx.accounts.new_loan;
+
+        new_loan.lending_offer = lending_offer_key;
+        new_loan.asset_pair_market = lending_offer.asset_pair_market;
+        new_loan.lender = lending_offer.lender;
+        new_loan.borrower = ctx.accounts.borrower.key();
+        new_loan.principal_amount = debt;
+        new_loan.collateral_amount = collateral_amount;
+        new_loan.interest_rate_bps = lending_offer.interest_rate_bps;
+        new_loan.ltv_bps = lending_offer.ltv_bps;
+        new_loan.interest_mode = lending_offer.interest_mode;
+        new_loan.penalty_rate_bps = lending_offer.penalty_rate_bps;
+        new_loan.notice_duration = lending_offer.notice_duration;
+        new_loan.origination_loan_value = u64::try_from(loan_value)
+            .map_err(|_| ErrorCode::PriceCalculationOverflow)?;
+        new_loan.origination_collateral_value = u64::try_from(collateral_value)
+            .map_err(|_| ErrorCode::PriceCalculationOverflow)?;
+        new_loan.loan_start_time = current_time;
+        new_loan.last_interest_update = current_time;
+        new_loan.accrued_interest = 0;
+        new_loan.last_rate_index = rate_index;
+        new_loan.repayment_deadline = None;
+        new_loan.maturity = lending_offer.maturity(current_time)?;
+        new_loan.is_active = true;
+        new_loan.bump = ctx.bumps.new_loan;
+    }
+
+    // Mark offer as inactive once the escrow is drained
+    let lending_offer = &mut ctx.accounts.lending_offer;
+    lending_offer.remaining_amount = lending_offer
+        .remaining_amount
+        .checked_sub(debt)
+        .ok_or(ErrorCode::InsufficientFunds)?;
+    if lending_offer.remaining_amount == 0 {
+        lending_offer.is_active = false;
+    }
+    ctx.accounts
+        .orderbook
+        .load_mut()?
+        .update_remaining(&lending_offer.key(), lending_offer.remaining_amount);
+
+    emit!(LoanRefinanced {
+        old_loan: old_loan_key,
+        new_loan: ctx.accounts.new_loan.key(),
+        borrower: ctx.accounts.borrower.key(),
+        old_lender,
+        new_lender: lending_offer.lender,
+        debt_repaid: debt,
+        lender_fee,
+        borrower_fee,
+        collateral_amount,
+    });
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer, CloseAccount};
 use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
 use crate::state::Loan;
 use crate::error::ErrorCode;
 
@@ -9,16 +10,21 @@ pub struct RepayLoan<'info> {
     #[account(mut)]
     pub borrower: Signer<'info>,
 
+    // Not checked against the emergency pause: borrowers can always repay and exit
     pub lending_market: Account<'info, LendingMarket>,
 
     #[account(
         mut,
         close = borrower,
         has_one = borrower,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
         constraint = loan.is_active @ ErrorCode::LoanNotActive,
     )]
     pub loan: Account<'info, Loan>,
 
+    #[account(mut)]
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+
     #[account(
         mut,
         seeds = [Loan::COLLATERAL_SEED,loan.key().as_ref()],
@@ -29,6 +35,7 @@ pub struct RepayLoan<'info> {
     #[account(
         mut,
         constraint = borrower_loan_token_account.owner == borrower.key(),
+        constraint = borrower_loan_token_account.mint == asset_pair_market.loan_mint,
     )]
     pub borrower_loan_token_account: Account<'info, TokenAccount>,
 
@@ -48,6 +55,7 @@ pub struct RepayLoan<'info> {
     #[account(
         mut,
         constraint = lender_token_account.owner == lender.key(),
+        constraint = lender_token_account.mint == asset_pair_market.loan_mint,
     )]
     pub lender_token_account: Account<'info, TokenAccount>,
 
@@ -61,6 +69,7 @@ pub struct RepayLoan<'info> {
     #[account(
         mut,
         constraint = fee_recipient_token_account.owner == fee_recipient.key(),
+        constraint = fee_recipient_token_account.mint == asset_pair_market.loan_mint,
     )]
     pub fee_recipient_token_account: Account<'info, TokenAccount>,
 
@@ -73,7 +82,11 @@ pub fn repay_loan_handler(ctx: Context<RepayLoan>) -> Result<()> {
     let current_time = Clock::get()?.unix_timestamp;
 
     // Calculate total repayment amount (principal + interest)
-    let total_repayment = loan.calculate_repayment_amount(current_time)?;
+    let total_repayment = loan.calculate_repayment_amount(current_time, &ctx.accounts.asset_pair_market)?;
+
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+    asset_pair_market.update_rate_index(current_time)?;
+    asset_pair_market.total_borrowed = asset_pair_market.total_borrowed.saturating_sub(loan.principal_amount);
 
     // Calculate lender fee (2%)
     let lender_fee = total_repayment
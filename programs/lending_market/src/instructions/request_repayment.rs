@@ -1,11 +1,25 @@
 use anchor_lang::prelude::*;
+use crate::instructions::init_lending_market::LendingMarket;
 use crate::state::Loan;
 use crate::error::ErrorCode;
 
+#[event]
+pub struct RepaymentRequested {
+    pub loan: Pubkey,
+    pub lender: Pubkey,
+    pub borrower: Pubkey,
+    pub repayment_deadline: i64,
+}
+
 #[derive(Accounts)]
 pub struct RequestRepayment<'info> {
     pub lender: Signer<'info>,
 
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
     #[account(
         mut,
         has_one = lender,
@@ -14,12 +28,28 @@ pub struct RequestRepayment<'info> {
     pub loan: Account<'info, Loan>,
 }
 
+/// A pending request can't be re-issued, use extend_repayment_deadline or cancel_repayment_request.
+/// Fixed-term loans can only be called from maturity, they become due after the grace period anyway.
 pub fn request_repayment_handler(ctx: Context<RequestRepayment>) -> Result<()> {
     let loan = &mut ctx.accounts.loan;
     let current_time = Clock::get()?.unix_timestamp;
 
-    // Set repayment deadline to 48 hours from now
-    loan.repayment_deadline = Some(current_time + Loan::REPAYMENT_NOTICE_DURATION);
+    require!(loan.repayment_deadline.is_none(), ErrorCode::RepaymentAlreadyRequested);
+    // Fixed-term borrowers keep the funds until maturity
+    if let Some(maturity) = loan.maturity {
+        require!(current_time >= maturity, ErrorCode::LoanNotMatured);
+    }
+
+    // Deadline is the notice period agreed on the offer from now
+    let repayment_deadline = current_time + loan.notice_duration;
+    loan.repayment_deadline = Some(repayment_deadline);
+
+    emit!(RepaymentRequested {
+        loan: loan.key(),
+        lender: loan.lender,
+        borrower: loan.borrower,
+        repayment_deadline,
+    });
 
     Ok(())
-}
\ No newline at end of file
+}
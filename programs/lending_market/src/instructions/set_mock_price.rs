@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::init_mock_price_feed::MockPriceFeed;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct SetMockPrice<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
+    #[account(
+        mut,
+        seeds = [MockPriceFeed::SEED, mock_price_feed.mint.as_ref()],
+        bump = mock_price_feed.bump,
+    )]
+    pub mock_price_feed: Account<'info, MockPriceFeed>,
+}
+
+pub fn set_mock_price_handler(ctx: Context<SetMockPrice>, price: i64) -> Result<()> {
+    require!(price > 0, ErrorCode::InvalidPrice);
+
+    // Publishing also refreshes the timestamp so the price is not considered stale
+    let mock_price_feed = &mut ctx.accounts.mock_price_feed;
+    mock_price_feed.price = price;
+    mock_price_feed.publish_time = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct ProtocolPauseUpdated {
+    pub authority: Pubkey,
+    pub is_paused: bool,
+}
+
+#[derive(Accounts)]
+pub struct SetProtocolPaused<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LendingMarket::SEED],
+        bump = lending_market.bump,
+        constraint = authority.key() == lending_market.admin
+            || authority.key() == lending_market.guardian @ ErrorCode::Unauthorized,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+}
+
+/// While paused: create_lending_offer, take_loan, request_repayment and liquidate_loan are rejected.
+/// repay_loan and cancel_lending_offer never check the flag so users can always exit.
+/// The guardian can only pause; lifting the pause is admin-only.
+pub fn set_protocol_paused_handler(ctx: Context<SetProtocolPaused>, is_paused: bool) -> Result<()> {
+    require!(
+        is_paused || ctx.accounts.authority.key() == ctx.accounts.lending_market.admin,
+        ErrorCode::Unauthorized
+    );
+    ctx.accounts.lending_market.is_paused = is_paused;
+
+    emit!(ProtocolPauseUpdated {
+        authority: ctx.accounts.authority.key(),
+        is_paused,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer, CloseAccount};
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::start_collateral_auction::CollateralAuction;
+use crate::state::Loan;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct CollateralAuctionSettled {
+    pub auction: Pubkey,
+    pub loan: Pubkey,
+    pub collateral_amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct SettleCollateralAuction<'info> {
+    #[account(mut)]
+    pub lender: Signer<'info>,
+
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
+    #[account(
+        mut,
+        close = lender,
+        has_one = loan,
+        has_one = lender,
+        seeds = [CollateralAuction::SEED, loan.key().as_ref()],
+        bump = collateral_auction.bump,
+    )]
+    pub collateral_auction: Account<'info, CollateralAuction>,
+
+    #[account(
+        mut,
+        close = lender,
+    )]
+    pub loan: Account<'info, Loan>,
+
+    #[account(
+        mut,
+        seeds = [Loan::COLLATERAL_SEED,loan.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = lender_token_account.owner == lender.key(),
+    )]
+    pub lender_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// No bid by the time the price reached its floor: the lender takes all the collateral
+pub fn settle_collateral_auction_handler(ctx: Context<SettleCollateralAuction>) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.collateral_auction.has_reached_floor(current_time),
+        ErrorCode::AuctionNotEnded
+    );
+
+    let loan_key = ctx.accounts.loan.key();
+    let collateral_amount = ctx.accounts.collateral_vault.amount;
+
+    // Create collateral vault authority seeds
+    let collateral_seeds = &[
+        Loan::COLLATERAL_SEED,
+        loan_key.as_ref(),
+        &[ctx.bumps.collateral_vault],
+    ];
+    let signer_seeds = &[&collateral_seeds[..]];
+
+    // Transfer all collateral to lender
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.collateral_vault.to_account_info(),
+        to: ctx.accounts.lender_token_account.to_account_info(),
+        authority: ctx.accounts.collateral_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, collateral_amount)?;
+
+    // Close collateral vault account
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.collateral_vault.to_account_info(),
+        destination: ctx.accounts.lender.to_account_info(),
+        authority: ctx.accounts.collateral_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::close_account(cpi_ctx)?;
+
+    emit!(CollateralAuctionSettled {
+        auction: ctx.accounts.collateral_auction.key(),
+        loan: loan_key,
+        collateral_amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::oracle::OraclePrice;
+use crate::state::Loan;
+use crate::error::ErrorCode;
+
+/// Dutch auction of a liquidatable loan's collateral, priced in loan tokens for the whole lot
+#[account]
+#[derive(InitSpace)]
+pub struct CollateralAuction {
+    pub loan: Pubkey,
+    pub lender: Pubkey,
+    pub borrower: Pubkey,
+    pub asset_pair_market: Pubkey,
+    pub collateral_amount: u64,
+    pub debt: u64,          // principal + interest owed to the lender, frozen at start
+    pub start_price: u64,   // loan tokens, oracle value of the collateral plus START_PREMIUM_BPS
+    pub floor_price: u64,   // loan tokens, oracle value of the collateral minus FLOOR_DISCOUNT_BPS
+    pub start_time: i64,
+    pub bump: u8,
+}
+
+/// seeds = [CollateralAuction::SEED, loan.key().as_ref()]
+impl CollateralAuction {
+    pub const SEED: &'static [u8] = b"auction";
+    pub const START_PREMIUM_BPS: u64 = 1000; // starts at 110% of oracle value
+    pub const FLOOR_DISCOUNT_BPS: u64 = 2000; // decays to 80% of oracle value
+    pub const DURATION: i64 = 60 * 60; // 1 hour from start to floor
+
+    /// Price decays linearly from `start_price` to `floor_price` over `DURATION`
+    pub fn current_price(&self, current_time: i64) -> u64 {
+        let elapsed = current_time.saturating_sub(self.start_time).clamp(0, Self::DURATION);
+        let decay = (self.start_price - self.floor_price) as u128 * elapsed as u128
+            / Self::DURATION as u128;
+
+        self.start_price - decay as u64
+    }
+
+    pub fn has_reached_floor(&self, current_time: i64) -> bool {
+        current_time >= self.start_time + Self::DURATION
+    }
+}
+
+#[event]
+pub struct CollateralAuctionStarted {
+    pub auction: Pubkey,
+    pub loan: Pubkey,
+    pub collateral_amount: u64,
+    pub debt: u64,
+    pub start_price: u64,
+    pub floor_price: u64,
+}
+
+#[derive(Accounts)]
+pub struct StartCollateralAuction<'info> {
+    #[account(mut)]
+    pub lender: Signer<'info>,
+
+    // Liquidations are oracle driven, halt them with the rest of the protocol
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
+    #[account(
+        mut,
+        has_one = lender,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        constraint = loan.is_active @ ErrorCode::LoanNotActive,
+    )]
+    pub loan: Account<'info, Loan>,
+
+    #[account(mut)]
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = loan_price_feed.key() == asset_pair_market.loan_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub loan_price_feed: AccountInfo<'info>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = collateral_price_feed.key() == asset_pair_market.collateral_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub collateral_price_feed: AccountInfo<'info>,
+
+    #[account(
+        seeds = [Loan::COLLATERAL_SEED,loan.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = lender,
+        space = 8 + CollateralAuction::INIT_SPACE,
+        seeds = [CollateralAuction::SEED, loan.key().as_ref()],
+        bump,
+    )]
+    pub collateral_auction: Account<'info, CollateralAuction>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Alternative to liquidate_loan: the loan is frozen (inactive) and its collateral stays
+/// in the vault until bid_collateral_auction or settle_collateral_auction closes it
+pub fn start_collateral_auction_handler(ctx: Context<StartCollateralAuction>) -> Result<()> {
+    let loan = &mut ctx.accounts.loan;
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let loan_price = OraclePrice::load(&ctx.accounts.loan_price_feed, &asset_pair_market.loan_feed_id, current_time)?;
+    let collateral_price = OraclePrice::load(&ctx.accounts.collateral_price_feed, &asset_pair_market.collateral_feed_id, current_time)?;
+
+    // Verify loan can be liquidated
+    require!(
+        loan.can_liquidate(current_time, asset_pair_market, &loan_price, &collateral_price)?,
+        ErrorCode::CannotLiquidateHealthyLoan
+    );
+
+    // Oracle value of the whole lot expressed in loan tokens
+    let collateral_amount = ctx.accounts.collateral_vault.amount;
+    let collateral_value = collateral_price.value(collateral_amount, asset_pair_market.collateral_decimals)?;
+    let collateral_in_loan_tokens = loan_price.amount_for_value(collateral_value, asset_pair_market.loan_decimals)?;
+
+    let start_price = (collateral_in_loan_tokens as u128)
+        .checked_mul(10000 + CollateralAuction::START_PREMIUM_BPS as u128)
+        .ok_or(ErrorCode::PriceCalculationOverflow)?
+        / 10000;
+    let floor_price = (collateral_in_loan_tokens as u128)
+        .checked_mul(10000 - CollateralAuction::FLOOR_DISCOUNT_BPS as u128)
+        .ok_or(ErrorCode::PriceCalculationOverflow)?
+        / 10000;
+
+    let debt = loan.calculate_repayment_amount(current_time, asset_pair_market)?;
+    loan.is_active = false;
+
+    // The frozen loan no longer counts towards the pair's utilization
+    asset_pair_market.update_rate_index(current_time)?;
+    asset_pair_market.total_borrowed = asset_pair_market.total_borrowed.saturating_sub(loan.principal_amount);
+
+    let collateral_auction = &mut ctx.accounts.collateral_auction;
+    collateral_auction.loan = loan.key();
+    collateral_auction.lender = loan.lender;
+    collateral_auction.borrower = loan.borrower;
+    collateral_auction.asset_pair_market = loan.asset_pair_market;
+    collateral_auction.collateral_amount = collateral_amount;
+    collateral_auction.debt = debt;
+    collateral_auction.start_price = u64::try_from(start_price)
+        .map_err(|_| ErrorCode::PriceCalculationOverflow)?;
+    collateral_auction.floor_price = floor_price as u64;
+    collateral_auction.start_time = current_time;
+    collateral_auction.bump = ctx.bumps.collateral_auction;
+
+    emit!(CollateralAuctionStarted {
+        auction: collateral_auction.key(),
+        loan: collateral_auction.loan,
+        collateral_amount,
+        debt,
+        start_price: collateral_auction.start_price,
+        floor_price: collateral_auction.floor_price,
+    });
+
+    Ok(())
+}
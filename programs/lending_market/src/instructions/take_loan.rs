@@ -3,23 +3,35 @@ use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use crate::instructions::init_lending_market::LendingMarket;
 use crate::instructions::create_asset_pair_market::AssetPairMarket;
 use crate::instructions::cancel_lending_offer::LendingOffer;
-use crate::state::Loan;
+use crate::oracle::OraclePrice;
+use crate::state::{Loan, Orderbook};
 use crate::error::ErrorCode;
 
 #[derive(Accounts)]
-#[instruction(collateral_amount: u64)]
 pub struct TakeLoan<'info> {
     #[account(mut)]
     pub borrower: Signer<'info>,
 
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
     pub lending_market: Box<Account<'info, LendingMarket>>,
 
     #[account(
+        mut,
+        constraint = asset_pair_market.is_active @ ErrorCode::MarketNotActive,
         constraint = asset_pair_market.loan_mint == loan_mint.key() @ ErrorCode::InvalidAssetPair,
         constraint = asset_pair_market.collateral_mint == collateral_mint.key() @ ErrorCode::InvalidAssetPair,
     )]
     pub asset_pair_market: Box<Account<'info, AssetPairMarket>>,
 
+    #[account(
+        mut,
+        seeds = [Orderbook::SEED,asset_pair_market.key().as_ref()],
+        bump,
+    )]
+    pub orderbook: AccountLoader<'info, Orderbook>,
+
     #[account(mut,has_one = asset_pair_market,constraint = lending_offer.is_active @ ErrorCode::OfferNotActive)]
     pub lending_offer: Box<Account<'info, LendingOffer>>,
 
@@ -55,6 +67,18 @@ pub struct TakeLoan<'info> {
     /// CHECK: Validated against asset_pair_market
     pub collateral_mint: AccountInfo<'info>,
 
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = loan_price_feed.key() == asset_pair_market.loan_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub loan_price_feed: AccountInfo<'info>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = collateral_price_feed.key() == asset_pair_market.collateral_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub collateral_price_feed: AccountInfo<'info>,
+
     #[account(
         mut,
         constraint = borrower_loan_token_account.owner == borrower.key(),
@@ -87,41 +111,70 @@ pub struct TakeLoan<'info> {
     pub system_program: Program<'info, System>,
 }
 
-/// 1. Validate collateral amount based on LTV
+/// 1. Validate borrow amount against remaining offer and minimum fill,
+///    then collateral value against loan value at oracle prices and LTV
 /// 2. Calculate borrower fee (1%)
 /// 3. Transfer collateral from borrower to collateral vault
 /// 4. Create escrow authority seeds
 /// 5. Transfer loan amount (minus fee) from escrow to borrower
 /// 6. Transfer fee from escrow to fee recipient
 /// 7. Initialize loan
-/// 8. Mark offer as inactive since it's been taken
-
-pub fn take_loan_handler(ctx: Context<TakeLoan>, collateral_amount: u64) -> Result<()> {
+/// 8. Reduce remaining offer amount, mark offer inactive once fully taken and update the orderbook
+pub fn take_loan_handler(
+    ctx: Context<TakeLoan>,
+    borrow_amount: u64,
+    collateral_amount: u64,
+) -> Result<()> {
     let lending_offer = &ctx.accounts.lending_offer;
     let lending_market = &ctx.accounts.lending_market;
-    let loan_amount = lending_offer.loan_amount;
+    let asset_pair_market = &ctx.accounts.asset_pair_market;
+
+    require!(borrow_amount > 0, ErrorCode::InvalidLoanAmount);
+    require!(
+        borrow_amount <= lending_offer.remaining_amount,
+        ErrorCode::InsufficientFunds
+    );
+    // The last remainder of an offer can always be taken even if below the minimum fill
+    require!(
+        borrow_amount >= lending_offer.min_fill_amount
+            || borrow_amount == lending_offer.remaining_amount,
+        ErrorCode::FillBelowMinimum
+    );
 
-    // Validate collateral amount based on LTV
-    // Required collateral = (loan_value / ltv_bps) * 10000
-    let required_collateral = (loan_amount as u128)
-        .checked_mul(10000)
-        .ok_or(ErrorCode::InvalidCollateralAmount)?
-        .checked_div(lending_offer.ltv_bps as u128)
-        .ok_or(ErrorCode::InvalidCollateralAmount)? as u64;
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(!lending_offer.is_expired(current_time), ErrorCode::OfferExpired);
+
+    // The pair's liquidation LTV may have been lowered since the offer was created
+    require!(lending_offer.ltv_bps < asset_pair_market.liquidation_ltv_bps, ErrorCode::InvalidLTV);
+
+    // Validate collateral amount based on LTV, valued with both mints' decimals and oracle prices
+    let loan_price = OraclePrice::load(&ctx.accounts.loan_price_feed, &ctx.accounts.asset_pair_market.loan_feed_id, current_time)?;
+    let collateral_price = OraclePrice::load(&ctx.accounts.collateral_price_feed, &ctx.accounts.asset_pair_market.collateral_feed_id, current_time)?;
+
+    let required_collateral = asset_pair_market.required_collateral(
+        borrow_amount,
+        lending_offer.ltv_bps,
+        &loan_price,
+        &collateral_price,
+    )?;
 
     require!(
         collateral_amount >= required_collateral,
         ErrorCode::InvalidCollateralAmount
     );
 
+    let loan_value = loan_price.value(borrow_amount, asset_pair_market.loan_decimals)?;
+    let collateral_value =
+        collateral_price.value(collateral_amount, asset_pair_market.collateral_decimals)?;
+
     // Calculate borrower fee (1%)
-    let borrower_fee = loan_amount
+    let borrower_fee = borrow_amount
         .checked_mul(lending_market.borrower_fee_bps)
         .ok_or(ErrorCode::InterestCalculationOverflow)?
         .checked_div(10000)
         .ok_or(ErrorCode::InterestCalculationOverflow)?;
 
-    let borrower_receives = loan_amount
+    let borrower_receives = borrow_amount
         .checked_sub(borrower_fee)
         .ok_or(ErrorCode::InterestCalculationOverflow)?;
 
@@ -169,28 +222,55 @@ pub fn take_loan_handler(ctx: Context<TakeLoan>, collateral_amount: u64) -> Resu
     );
     token::transfer(cpi_ctx, borrower_fee)?;
 
+    // Checkpoint the variable rate before utilization moves
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+    asset_pair_market.update_rate_index(current_time)?;
+    asset_pair_market.total_available = asset_pair_market.total_available.saturating_sub(borrow_amount);
+    asset_pair_market.total_borrowed = asset_pair_market.total_borrowed.saturating_add(borrow_amount);
+    let rate_index = asset_pair_market.rate_index;
+
     { 
         // Initialize loan
         let loan = &mut ctx.accounts.loan;
-        let current_time = Clock::get()?.unix_timestamp;
 
         loan.lending_offer = lending_offer.key();
+        loan.asset_pair_market = lending_offer.asset_pair_market;
         loan.lender = lending_offer.lender;
         loan.borrower = ctx.accounts.borrower.key();
-        loan.principal_amount = loan_amount;
+        loan.principal_amount = borrow_amount;
         loan.collateral_amount = collateral_amount;
         loan.interest_rate_bps = lending_offer.interest_rate_bps;
         loan.ltv_bps = lending_offer.ltv_bps;
+        loan.interest_mode = lending_offer.interest_mode;
+        loan.penalty_rate_bps = lending_offer.penalty_rate_bps;
+        loan.notice_duration = lending_offer.notice_duration;
+        loan.origination_loan_value = u64::try_from(loan_value)
+            .map_err(|_| ErrorCode::PriceCalculationOverflow)?;
+        loan.origination_collateral_value = u64::try_from(collateral_value)
+            .map_err(|_| ErrorCode::PriceCalculationOverflow)?;
         loan.loan_start_time = current_time;
         loan.last_interest_update = current_time;
+        loan.accrued_interest = 0;
+        loan.last_rate_index = rate_index;
         loan.repayment_deadline = None;
+        loan.maturity = lending_offer.maturity(current_time)?;
         loan.is_active = true;
         loan.bump = ctx.bumps.loan;
     }
 
-    // Mark offer as inactive since it's been taken
+    // Mark offer as inactive once the escrow is drained
     let lending_offer = &mut ctx.accounts.lending_offer;
-    lending_offer.is_active = false;
+    lending_offer.remaining_amount = lending_offer
+        .remaining_amount
+        .checked_sub(borrow_amount)
+        .ok_or(ErrorCode::InsufficientFunds)?;
+    if lending_offer.remaining_amount == 0 {
+        lending_offer.is_active = false;
+    }
+    ctx.accounts
+        .orderbook
+        .load_mut()?
+        .update_remaining(&lending_offer.key(), lending_offer.remaining_amount);
 
     Ok(())
 }
\ No newline at end of file
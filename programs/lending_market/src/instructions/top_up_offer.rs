@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::instructions::cancel_lending_offer::LendingOffer;
+use crate::state::Orderbook;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct LendingOfferResized {
+    pub lending_offer: Pubkey,
+    pub loan_amount: u64,
+    pub remaining_amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct TopUpOffer<'info> {
+    pub lender: Signer<'info>,
+
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
+    #[account(
+        mut,
+        has_one = lender,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        constraint = lending_offer.is_active @ ErrorCode::OfferNotActive,
+    )]
+    pub lending_offer: Account<'info, LendingOffer>,
+
+    #[account(
+        mut,
+        constraint = asset_pair_market.is_active @ ErrorCode::MarketNotActive,
+    )]
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+
+    #[account(
+        mut,
+        seeds = [Orderbook::SEED,asset_pair_market.key().as_ref()],
+        bump,
+    )]
+    pub orderbook: AccountLoader<'info, Orderbook>,
+
+    #[account(
+        mut,
+        seeds = [LendingOffer::ESCROW_SEED,lending_offer.key().as_ref()],
+        bump,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = lender_token_account.owner == lender.key(),
+        constraint = lender_token_account.mint == asset_pair_market.loan_mint,
+    )]
+    pub lender_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Add capital to an open offer, loan_amount grows with remaining_amount so an untaken offer stays untaken
+pub fn top_up_offer_handler(ctx: Context<TopUpOffer>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidLoanAmount);
+
+    // Transfer loan tokens from lender to escrow
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.lender_token_account.to_account_info(),
+        to: ctx.accounts.escrow.to_account_info(),
+        authority: ctx.accounts.lender.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+    asset_pair_market.update_rate_index(Clock::get()?.unix_timestamp)?;
+    asset_pair_market.total_available = asset_pair_market.total_available.saturating_add(amount);
+
+    let lending_offer = &mut ctx.accounts.lending_offer;
+    lending_offer.loan_amount = lending_offer
+        .loan_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+    lending_offer.remaining_amount = lending_offer
+        .remaining_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::InterestCalculationOverflow)?;
+    ctx.accounts
+        .orderbook
+        .load_mut()?
+        .update_remaining(&lending_offer.key(), lending_offer.remaining_amount);
+
+    emit!(LendingOfferResized {
+        lending_offer: lending_offer.key(),
+        loan_amount: lending_offer.loan_amount,
+        remaining_amount: lending_offer.remaining_amount,
+    });
+
+    Ok(())
+}
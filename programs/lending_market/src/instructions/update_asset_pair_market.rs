@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::{AssetPairMarket, RateModel};
+use crate::state::Loan;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct AssetPairMarketUpdated {
+    pub asset_pair_market: Pubkey,
+    pub old_liquidation_ltv_bps: u64,
+    pub new_liquidation_ltv_bps: u64,
+    pub old_liquidation_penalty_bps: u64,
+    pub new_liquidation_penalty_bps: u64,
+    pub old_liquidation_bonus_bps: u64,
+    pub new_liquidation_bonus_bps: u64,
+    pub old_rate_model: Option<RateModel>,
+    pub new_rate_model: Option<RateModel>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAssetPairMarket<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
+    #[account(
+        mut,
+        seeds = [
+            AssetPairMarket::SEED,
+            asset_pair_market.loan_mint.as_ref(),
+            asset_pair_market.collateral_mint.as_ref()
+        ],
+        bump = asset_pair_market.bump,
+    )]
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+}
+
+/// Fields left as `None` keep their current value, so a rate model can be replaced but not removed
+pub fn update_asset_pair_market_handler(
+    ctx: Context<UpdateAssetPairMarket>,
+    liquidation_ltv_bps: Option<u64>,
+    liquidation_penalty_bps: Option<u64>,
+    liquidation_bonus_bps: Option<u64>,
+    rate_model: Option<RateModel>,
+) -> Result<()> {
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+
+    let new_liquidation_ltv_bps =
+        liquidation_ltv_bps.unwrap_or(asset_pair_market.liquidation_ltv_bps);
+    let new_liquidation_penalty_bps =
+        liquidation_penalty_bps.unwrap_or(asset_pair_market.liquidation_penalty_bps);
+    let new_liquidation_bonus_bps =
+        liquidation_bonus_bps.unwrap_or(asset_pair_market.liquidation_bonus_bps);
+
+    // Lowering the threshold applies to open loans too
+    require!(
+        new_liquidation_ltv_bps > 0 && new_liquidation_ltv_bps <= Loan::LIQUIDATION_LTV_BPS,
+        ErrorCode::InvalidLTV
+    );
+    require!(
+        new_liquidation_penalty_bps <= AssetPairMarket::MAX_LIQUIDATION_PENALTY_BPS,
+        ErrorCode::LiquidationPenaltyTooHigh
+    );
+    require!(
+        new_liquidation_bonus_bps <= AssetPairMarket::MAX_LIQUIDATION_BONUS_BPS,
+        ErrorCode::LiquidationBonusTooHigh
+    );
+    if let Some(rate_model) = &rate_model {
+        rate_model.validate()?;
+    }
+    let new_rate_model = rate_model.or(asset_pair_market.rate_model);
+
+    // Variable rate loans accrue at the old curve up to now
+    asset_pair_market.update_rate_index(Clock::get()?.unix_timestamp)?;
+
+    emit!(AssetPairMarketUpdated {
+        asset_pair_market: asset_pair_market.key(),
+        old_liquidation_ltv_bps: asset_pair_market.liquidation_ltv_bps,
+        new_liquidation_ltv_bps,
+        old_liquidation_penalty_bps: asset_pair_market.liquidation_penalty_bps,
+        new_liquidation_penalty_bps,
+        old_liquidation_bonus_bps: asset_pair_market.liquidation_bonus_bps,
+        new_liquidation_bonus_bps,
+        old_rate_model: asset_pair_market.rate_model,
+        new_rate_model,
+    });
+
+    asset_pair_market.liquidation_ltv_bps = new_liquidation_ltv_bps;
+    asset_pair_market.liquidation_penalty_bps = new_liquidation_penalty_bps;
+    asset_pair_market.liquidation_bonus_bps = new_liquidation_bonus_bps;
+    asset_pair_market.rate_model = new_rate_model;
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct LendingMarketUpdated {
+    pub old_fee_recipient: Pubkey,
+    pub new_fee_recipient: Pubkey,
+    pub old_lender_fee_bps: u64,
+    pub new_lender_fee_bps: u64,
+    pub old_borrower_fee_bps: u64,
+    pub new_borrower_fee_bps: u64,
+    pub old_guardian: Pubkey,
+    pub new_guardian: Pubkey,
+    pub old_min_notice_duration: i64,
+    pub new_min_notice_duration: i64,
+    pub old_max_notice_duration: i64,
+    pub new_max_notice_duration: i64,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLendingMarket<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+        seeds = [LendingMarket::SEED],
+        bump = lending_market.bump,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+}
+
+/// Fields left as `None` keep their current value
+pub fn update_lending_market_handler(
+    ctx: Context<UpdateLendingMarket>,
+    fee_recipient: Option<Pubkey>,
+    lender_fee_bps: Option<u64>,
+    borrower_fee_bps: Option<u64>,
+    guardian: Option<Pubkey>,
+    min_notice_duration: Option<i64>,
+    max_notice_duration: Option<i64>,
+) -> Result<()> {
+    let lending_market = &mut ctx.accounts.lending_market;
+
+    let new_fee_recipient = fee_recipient.unwrap_or(lending_market.fee_recipient);
+    let new_lender_fee_bps = lender_fee_bps.unwrap_or(lending_market.lender_fee_bps);
+    let new_borrower_fee_bps = borrower_fee_bps.unwrap_or(lending_market.borrower_fee_bps);
+    let new_guardian = guardian.unwrap_or(lending_market.guardian);
+    let new_min_notice_duration = min_notice_duration.unwrap_or(lending_market.min_notice_duration);
+    let new_max_notice_duration = max_notice_duration.unwrap_or(lending_market.max_notice_duration);
+
+    require!(new_fee_recipient != Pubkey::default(), ErrorCode::InvalidFeeRecipient);
+    require!(new_lender_fee_bps <= LendingMarket::MAX_LENDER_FEE_BPS, ErrorCode::FeeTooHigh);
+    require!(new_borrower_fee_bps <= LendingMarket::MAX_BORROWER_FEE_BPS, ErrorCode::FeeTooHigh);
+    // Only bounds new offers, existing offers and loans keep their notice period
+    require!(
+        new_min_notice_duration > 0
+            && new_min_notice_duration <= new_max_notice_duration
+            && new_max_notice_duration <= LendingMarket::MAX_NOTICE_DURATION,
+        ErrorCode::InvalidNoticeDuration
+    );
+
+    emit!(LendingMarketUpdated {
+        old_fee_recipient: lending_market.fee_recipient,
+        new_fee_recipient,
+        old_lender_fee_bps: lending_market.lender_fee_bps,
+        new_lender_fee_bps,
+        old_borrower_fee_bps: lending_market.borrower_fee_bps,
+        new_borrower_fee_bps,
+        old_guardian: lending_market.guardian,
+        new_guardian,
+        old_min_notice_duration: lending_market.min_notice_duration,
+        new_min_notice_duration,
+        old_max_notice_duration: lending_market.max_notice_duration,
+        new_max_notice_duration,
+    });
+
+    lending_market.fee_recipient = new_fee_recipient;
+    lending_market.lender_fee_bps = new_lender_fee_bps;
+    lending_market.borrower_fee_bps = new_borrower_fee_bps;
+    lending_market.guardian = new_guardian;
+    lending_market.min_notice_duration = new_min_notice_duration;
+    lending_market.max_notice_duration = new_max_notice_duration;
+
+    Ok(())
+}
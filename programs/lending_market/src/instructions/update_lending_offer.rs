@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::instructions::cancel_lending_offer::LendingOffer;
+use crate::state::{InterestMode, Orderbook, OrderbookEntry};
+use crate::error::ErrorCode;
+
+#[event]
+pub struct LendingOfferUpdated {
+    pub lending_offer: Pubkey,
+    pub old_interest_rate_bps: u64,
+    pub new_interest_rate_bps: u64,
+    pub old_ltv_bps: u64,
+    pub new_ltv_bps: u64,
+    pub old_min_fill_amount: u64,
+    pub new_min_fill_amount: u64,
+    pub old_penalty_rate_bps: u64,
+    pub new_penalty_rate_bps: u64,
+    pub old_notice_duration: i64,
+    pub new_notice_duration: i64,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLendingOffer<'info> {
+    pub lender: Signer<'info>,
+
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
+    #[account(
+        mut,
+        has_one = lender,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        constraint = lending_offer.is_active @ ErrorCode::OfferNotActive,
+    )]
+    pub lending_offer: Account<'info, LendingOffer>,
+
+    #[account(
+        constraint = asset_pair_market.is_active @ ErrorCode::MarketNotActive,
+    )]
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+
+    #[account(
+        mut,
+        seeds = [Orderbook::SEED,asset_pair_market.key().as_ref()],
+        bump,
+    )]
+    pub orderbook: AccountLoader<'info, Orderbook>,
+}
+
+/// Change the terms of an untaken offer in place, fields left as `None` keep their current value.
+/// Terms are validated as in create_lending_offer
+pub fn update_lending_offer_handler(
+    ctx: Context<UpdateLendingOffer>,
+    interest_rate_bps: Option<u64>,
+    ltv_bps: Option<u64>,
+    min_fill_amount: Option<u64>,
+    penalty_rate_bps: Option<u64>,
+    notice_duration: Option<i64>,
+) -> Result<()> {
+    let lending_market = &ctx.accounts.lending_market;
+    let lending_offer = &mut ctx.accounts.lending_offer;
+
+    // Loans already taken keep the terms they were opened with
+    require!(
+        lending_offer.remaining_amount == lending_offer.loan_amount,
+        ErrorCode::OfferAlreadyTaken
+    );
+
+    let new_interest_rate_bps = interest_rate_bps.unwrap_or(lending_offer.interest_rate_bps);
+    let new_ltv_bps = ltv_bps.unwrap_or(lending_offer.ltv_bps);
+    let new_min_fill_amount = min_fill_amount.unwrap_or(lending_offer.min_fill_amount);
+    let new_penalty_rate_bps = penalty_rate_bps.unwrap_or(lending_offer.penalty_rate_bps);
+    let new_notice_duration = notice_duration.unwrap_or(lending_offer.notice_duration);
+
+    require!(new_min_fill_amount <= lending_offer.loan_amount, ErrorCode::InvalidLoanAmount);
+    require!(new_interest_rate_bps <= 10000, ErrorCode::InvalidInterestRate);
+    require!(new_penalty_rate_bps <= 10000, ErrorCode::InvalidInterestRate);
+    require!(
+        new_notice_duration >= lending_market.min_notice_duration
+            && new_notice_duration <= lending_market.max_notice_duration,
+        ErrorCode::InvalidNoticeDuration
+    );
+    require!(new_ltv_bps > 0 && new_ltv_bps <= 10000, ErrorCode::InvalidLTV);
+    // Loans must not be liquidatable at origination
+    require!(new_ltv_bps < ctx.accounts.asset_pair_market.liquidation_ltv_bps, ErrorCode::InvalidLTV);
+    if lending_offer.interest_mode == InterestMode::Variable {
+        require!(new_interest_rate_bps == 0, ErrorCode::InvalidInterestRate);
+    }
+
+    emit!(LendingOfferUpdated {
+        lending_offer: lending_offer.key(),
+        old_interest_rate_bps: lending_offer.interest_rate_bps,
+        new_interest_rate_bps,
+        old_ltv_bps: lending_offer.ltv_bps,
+        new_ltv_bps,
+        old_min_fill_amount: lending_offer.min_fill_amount,
+        new_min_fill_amount,
+        old_penalty_rate_bps: lending_offer.penalty_rate_bps,
+        new_penalty_rate_bps,
+        old_notice_duration: lending_offer.notice_duration,
+        new_notice_duration,
+    });
+
+    lending_offer.interest_rate_bps = new_interest_rate_bps;
+    lending_offer.ltv_bps = new_ltv_bps;
+    lending_offer.min_fill_amount = new_min_fill_amount;
+    lending_offer.penalty_rate_bps = new_penalty_rate_bps;
+    lending_offer.notice_duration = new_notice_duration;
+
+    if lending_offer.interest_mode != InterestMode::Variable {
+        ctx.accounts.orderbook.load_mut()?.update_entry(OrderbookEntry {
+            lending_offer: lending_offer.key(),
+            interest_rate_bps: new_interest_rate_bps,
+            ltv_bps: new_ltv_bps,
+            remaining_amount: lending_offer.remaining_amount,
+            min_fill_amount: new_min_fill_amount,
+        });
+    }
+
+    Ok(())
+}
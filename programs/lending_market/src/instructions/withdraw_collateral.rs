@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::instructions::init_lending_market::LendingMarket;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::oracle::OraclePrice;
+use crate::state::Loan;
+use crate::error::ErrorCode;
+
+#[event]
+pub struct CollateralWithdrawn {
+    pub loan: Pubkey,
+    pub amount: u64,
+    pub collateral_amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCollateral<'info> {
+    pub borrower: Signer<'info>,
+
+    #[account(
+        constraint = !lending_market.is_paused @ ErrorCode::ProtocolPaused,
+    )]
+    pub lending_market: Account<'info, LendingMarket>,
+
+    #[account(
+        mut,
+        has_one = borrower,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        constraint = loan.is_active @ ErrorCode::LoanNotActive,
+    )]
+    pub loan: Account<'info, Loan>,
+
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = loan_price_feed.key() == asset_pair_market.loan_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub loan_price_feed: AccountInfo<'info>,
+
+    /// CHECK: Validated against asset_pair_market, parsed by the oracle layer
+    #[account(
+        constraint = collateral_price_feed.key() == asset_pair_market.collateral_price_feed @ ErrorCode::InvalidPriceFeed,
+    )]
+    pub collateral_price_feed: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [Loan::COLLATERAL_SEED,loan.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = borrower_collateral_token_account.owner == borrower.key(),
+    )]
+    pub borrower_collateral_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Withdraw surplus collateral, the loan must stay within its ltv_bps at oracle prices
+pub fn withdraw_collateral_handler(ctx: Context<WithdrawCollateral>, amount: u64) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let loan = &mut ctx.accounts.loan;
+
+    require!(
+        amount > 0 && amount < loan.collateral_amount,
+        ErrorCode::InvalidCollateralAmount
+    );
+    loan.collateral_amount -= amount;
+
+    let loan_price = OraclePrice::load(&ctx.accounts.loan_price_feed, &ctx.accounts.asset_pair_market.loan_feed_id, current_time)?;
+    let collateral_price = OraclePrice::load(&ctx.accounts.collateral_price_feed, &ctx.accounts.asset_pair_market.collateral_feed_id, current_time)?;
+    let debt = loan.calculate_repayment_amount(current_time, &ctx.accounts.asset_pair_market)?;
+    let required_collateral = ctx.accounts.asset_pair_market.required_collateral(
+        debt,
+        loan.ltv_bps,
+        &loan_price,
+        &collateral_price,
+    )?;
+    require!(loan.collateral_amount >= required_collateral, ErrorCode::LTVExceedsMaximum);
+
+    // Transfer collateral from vault to borrower
+    let loan_key = loan.key();
+    let collateral_seeds = &[
+        Loan::COLLATERAL_SEED,
+        loan_key.as_ref(),
+        &[ctx.bumps.collateral_vault],
+    ];
+    let signer_seeds = &[&collateral_seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.collateral_vault.to_account_info(),
+        to: ctx.accounts.borrower_collateral_token_account.to_account_info(),
+        authority: ctx.accounts.collateral_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(CollateralWithdrawn {
+        loan: loan_key,
+        amount,
+        collateral_amount: ctx.accounts.loan.collateral_amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::instructions::cancel_lending_offer::LendingOffer;
+use crate::instructions::top_up_offer::LendingOfferResized;
+use crate::state::Orderbook;
+use crate::error::ErrorCode;
+
+/// Available while the protocol is paused so lenders can always withdraw
+#[derive(Accounts)]
+pub struct WithdrawFromOffer<'info> {
+    pub lender: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = lender,
+        has_one = asset_pair_market @ ErrorCode::InvalidAssetPair,
+        constraint = lending_offer.is_active @ ErrorCode::OfferNotActive,
+    )]
+    pub lending_offer: Account<'info, LendingOffer>,
+
+    #[account(mut)]
+    pub asset_pair_market: Account<'info, AssetPairMarket>,
+
+    #[account(
+        mut,
+        seeds = [Orderbook::SEED,asset_pair_market.key().as_ref()],
+        bump,
+    )]
+    pub orderbook: AccountLoader<'info, Orderbook>,
+
+    #[account(
+        mut,
+        seeds = [LendingOffer::ESCROW_SEED,lending_offer.key().as_ref()],
+        bump,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = lender_token_account.owner == lender.key(),
+    )]
+    pub lender_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Take part of the untaken capital back, withdrawing all of it is cancel_lending_offer.
+/// loan_amount shrinks with remaining_amount so an untaken offer stays untaken
+pub fn withdraw_from_offer_handler(ctx: Context<WithdrawFromOffer>, amount: u64) -> Result<()> {
+    let lending_offer = &ctx.accounts.lending_offer;
+    require!(
+        amount > 0 && amount < lending_offer.remaining_amount,
+        ErrorCode::InvalidLoanAmount
+    );
+
+    // Create escrow authority seeds
+    let offer_key = lending_offer.key();
+    let escrow_seeds = &[
+        LendingOffer::ESCROW_SEED,
+        offer_key.as_ref(),
+        &[ctx.bumps.escrow],
+    ];
+    let signer_seeds = &[&escrow_seeds[..]];
+
+    // Transfer tokens back from escrow to lender
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.escrow.to_account_info(),
+        to: ctx.accounts.lender_token_account.to_account_info(),
+        authority: ctx.accounts.escrow.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, amount)?;
+
+    let asset_pair_market = &mut ctx.accounts.asset_pair_market;
+    asset_pair_market.update_rate_index(Clock::get()?.unix_timestamp)?;
+    asset_pair_market.total_available = asset_pair_market.total_available.saturating_sub(amount);
+
+    let lending_offer = &mut ctx.accounts.lending_offer;
+    lending_offer.loan_amount -= amount;
+    lending_offer.remaining_amount -= amount;
+    ctx.accounts
+        .orderbook
+        .load_mut()?
+        .update_remaining(&offer_key, lending_offer.remaining_amount);
+
+    emit!(LendingOfferResized {
+        lending_offer: offer_key,
+        loan_amount: lending_offer.loan_amount,
+        remaining_amount: lending_offer.remaining_amount,
+    });
+
+    Ok(())
+}
@@ -2,9 +2,12 @@ use anchor_lang::prelude::*;
 
 pub mod error;
 pub mod instructions;
+pub mod math;
+pub mod oracle;
 pub mod state;
 
 use instructions::*;
+use state::InterestMode;
 
 declare_id!("EkAeaMi5vj8HEJ1wSc2cmeiFMqcWFgngp1J6F3wF6gq1");
 
@@ -27,18 +30,108 @@ pub mod lending_market {
         )
     }
 
+    /// only admin, `None` fields are left unchanged
+    pub fn update_lending_market(
+        ctx: Context<UpdateLendingMarket>,
+        fee_recipient: Option<Pubkey>,
+        lender_fee_bps: Option<u64>,
+        borrower_fee_bps: Option<u64>,
+        guardian: Option<Pubkey>,
+        min_notice_duration: Option<i64>,
+        max_notice_duration: Option<i64>,
+    ) -> Result<()> {
+        update_lending_market_handler(
+            ctx,
+            fee_recipient,
+            lender_fee_bps,
+            borrower_fee_bps,
+            guardian,
+            min_notice_duration,
+            max_notice_duration,
+        )
+    }
+
+    /// admin or guardian, emergency pause; repay_loan and cancel_lending_offer stay available
+    pub fn set_protocol_paused(ctx: Context<SetProtocolPaused>, is_paused: bool) -> Result<()> {
+        set_protocol_paused_handler(ctx, is_paused)
+    }
+
+    /// only admin, `None` cancels a pending proposal
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Option<Pubkey>) -> Result<()> {
+        propose_admin_handler(ctx, new_admin)
+    }
+
+    /// only pending admin
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        accept_admin_handler(ctx)
+    }
+
     /// only admin
-    pub fn create_asset_pair_market(ctx: Context<CreateAssetPairMarket>) -> Result<()> {
-        create_asset_pair_market_handler(ctx)
+    pub fn create_asset_pair_market(
+        ctx: Context<CreateAssetPairMarket>,
+        loan_feed_id: [u8; 32],
+        collateral_feed_id: [u8; 32],
+    ) -> Result<()> {
+        create_asset_pair_market_handler(ctx, loan_feed_id, collateral_feed_id)
     }
 
-    /// lender
+    /// only admin, blocks new offers and loans on the pair
+    pub fn deactivate_asset_pair_market(ctx: Context<DeactivateAssetPairMarket>) -> Result<()> {
+        deactivate_asset_pair_market_handler(ctx)
+    }
+
+    /// only admin
+    pub fn reactivate_asset_pair_market(ctx: Context<ReactivateAssetPairMarket>) -> Result<()> {
+        reactivate_asset_pair_market_handler(ctx)
+    }
+
+    /// only admin, `None` keeps the current value
+    pub fn update_asset_pair_market(
+        ctx: Context<UpdateAssetPairMarket>,
+        liquidation_ltv_bps: Option<u64>,
+        liquidation_penalty_bps: Option<u64>,
+        liquidation_bonus_bps: Option<u64>,
+        rate_model: Option<RateModel>,
+    ) -> Result<()> {
+        update_asset_pair_market_handler(
+            ctx,
+            liquidation_ltv_bps,
+            liquidation_penalty_bps,
+            liquidation_bonus_bps,
+            rate_model,
+        )
+    }
+
+    /// only admin, program-owned price account for tests and localnet
+    #[cfg(feature = "mock-oracle")]
+    pub fn init_mock_price_feed(
+        ctx: Context<InitMockPriceFeed>,
+        price: i64,
+        expo: i32,
+    ) -> Result<()> {
+        init_mock_price_feed_handler(ctx, price, expo)
+    }
+
+    /// only admin
+    #[cfg(feature = "mock-oracle")]
+    pub fn set_mock_price(ctx: Context<SetMockPrice>, price: i64) -> Result<()> {
+        set_mock_price_handler(ctx, price)
+    }
+
+    /// lender, `duration` makes loans fixed-term, `None` keeps them perpetual; `expires_at` bounds how long it can be taken
+    #[allow(clippy::too_many_arguments)]
     pub fn create_lending_offer(
         ctx: Context<CreateLendingOffer>,
         offer_id: u64,
         loan_amount: u64,
         interest_rate_bps: u64,
         ltv_bps: u64,
+        min_fill_amount: u64,
+        interest_mode: InterestMode,
+        penalty_rate_bps: u64,
+        notice_duration: i64,
+        duration: Option<i64>,
+        expires_at: Option<i64>,
     ) -> Result<()> {
         create_lending_offer_handler(
             ctx,
@@ -46,6 +139,12 @@ pub mod lending_market {
             loan_amount,
             interest_rate_bps,
             ltv_bps,
+            min_fill_amount,
+            interest_mode,
+            penalty_rate_bps,
+            notice_duration,
+            duration,
+            expires_at,
         )
     }
 
@@ -54,9 +153,62 @@ pub mod lending_market {
         cancel_lending_offer_handler(ctx)
     }
 
-    /// borrower
-    pub fn take_loan(ctx: Context<TakeLoan>, collateral_amount: u64) -> Result<()> {
-        take_loan_handler(ctx, collateral_amount)
+    /// lender, changes the terms of an untaken offer in place, `None` keeps the current value
+    pub fn update_lending_offer(
+        ctx: Context<UpdateLendingOffer>,
+        interest_rate_bps: Option<u64>,
+        ltv_bps: Option<u64>,
+        min_fill_amount: Option<u64>,
+        penalty_rate_bps: Option<u64>,
+        notice_duration: Option<i64>,
+    ) -> Result<()> {
+        update_lending_offer_handler(
+            ctx,
+            interest_rate_bps,
+            ltv_bps,
+            min_fill_amount,
+            penalty_rate_bps,
+            notice_duration,
+        )
+    }
+
+    /// lender, adds capital to the offer's escrow
+    pub fn top_up_offer(ctx: Context<TopUpOffer>, amount: u64) -> Result<()> {
+        top_up_offer_handler(ctx, amount)
+    }
+
+    /// lender, takes part of the untaken capital back
+    pub fn withdraw_from_offer(ctx: Context<WithdrawFromOffer>, amount: u64) -> Result<()> {
+        withdraw_from_offer_handler(ctx, amount)
+    }
+
+    /// anyone, closes an expired offer and returns its escrow to the lender
+    pub fn close_expired_offer(ctx: Context<CloseExpiredOffer>) -> Result<()> {
+        close_expired_offer_handler(ctx)
+    }
+
+    /// anyone, puts an active fixed-rate offer back on its pair's orderbook
+    pub fn index_lending_offer(ctx: Context<IndexLendingOffer>) -> Result<()> {
+        index_lending_offer_handler(ctx)
+    }
+
+    /// borrower, takes all or part of an offer
+    pub fn take_loan(
+        ctx: Context<TakeLoan>,
+        borrow_amount: u64,
+        collateral_amount: u64,
+    ) -> Result<()> {
+        take_loan_handler(ctx, borrow_amount, collateral_amount)
+    }
+
+    /// borrower, fills `amount` from the offers in remaining accounts, one Loan per offer
+    pub fn borrow_market<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BorrowMarket<'info>>,
+        amount: u64,
+        max_interest_rate_bps: u64,
+        collateral_amount: u64,
+    ) -> Result<()> {
+        borrow_market_handler(ctx, amount, max_interest_rate_bps, collateral_amount)
     }
 
     /// borrower
@@ -64,13 +216,97 @@ pub mod lending_market {
         repay_loan_handler(ctx)
     }
 
-    /// Request repayment with 48-hour notice
+    /// borrower, pays interest first then principal, can release collateral pro-rata
+    pub fn partial_repay(ctx: Context<PartialRepay>, amount: u64, release_collateral: bool) -> Result<()> {
+        partial_repay_handler(ctx, amount, release_collateral)
+    }
+
+    /// borrower, repay the loan from another offer's escrow on the same pair and move the collateral to the new loan
+    pub fn refinance_loan(ctx: Context<RefinanceLoan>) -> Result<()> {
+        refinance_loan_handler(ctx)
+    }
+
+    /// borrower, escrows collateral and asks for a loan any lender can fill
+    pub fn create_borrow_request(
+        ctx: Context<CreateBorrowRequest>,
+        request_id: u64,
+        loan_amount: u64,
+        collateral_amount: u64,
+        max_interest_rate_bps: u64,
+        ltv_bps: u64,
+        notice_duration: i64,
+    ) -> Result<()> {
+        create_borrow_request_handler(
+            ctx,
+            request_id,
+            loan_amount,
+            collateral_amount,
+            max_interest_rate_bps,
+            ltv_bps,
+            notice_duration,
+        )
+    }
+
+    /// borrower, returns the escrowed collateral
+    pub fn cancel_borrow_request(ctx: Context<CancelBorrowRequest>) -> Result<()> {
+        cancel_borrow_request_handler(ctx)
+    }
+
+    /// lender, funds a borrow request in full at a rate up to its maximum
+    pub fn fill_borrow_request(ctx: Context<FillBorrowRequest>, interest_rate_bps: u64) -> Result<()> {
+        fill_borrow_request_handler(ctx, interest_rate_bps)
+    }
+
+    /// borrower, tops up the collateral vault
+    pub fn deposit_collateral(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
+        deposit_collateral_handler(ctx, amount)
+    }
+
+    /// borrower, withdraws collateral while the loan stays within its LTV
+    pub fn withdraw_collateral(ctx: Context<WithdrawCollateral>, amount: u64) -> Result<()> {
+        withdraw_collateral_handler(ctx, amount)
+    }
+
+    /// Request repayment with the loan's notice period
     pub fn request_repayment(ctx: Context<RequestRepayment>) -> Result<()> {
         request_repayment_handler(ctx)
     }
 
-    /// Liquidate loan if deadline passed or LTV exceeds threshold
-    pub fn liquidate_loan(ctx: Context<LiquidateLoan>, current_ltv_bps: u64) -> Result<()> {
-        liquidate_loan_handler(ctx, current_ltv_bps)
+    /// lender, withdraw a pending repayment request
+    pub fn cancel_repayment_request(ctx: Context<CancelRepaymentRequest>) -> Result<()> {
+        cancel_repayment_request_handler(ctx)
+    }
+
+    /// lender, push a pending repayment deadline later
+    pub fn extend_repayment_deadline(
+        ctx: Context<ExtendRepaymentDeadline>,
+        new_repayment_deadline: i64,
+    ) -> Result<()> {
+        extend_repayment_deadline_handler(ctx, new_repayment_deadline)
+    }
+
+    /// Liquidate loan if deadline passed or oracle-priced LTV exceeds threshold, surplus collateral goes back to the borrower
+    pub fn liquidate_loan(ctx: Context<LiquidateLoan>) -> Result<()> {
+        liquidate_loan_handler(ctx)
+    }
+
+    /// anyone, repays an undercollateralized loan for collateral at the pair's liquidation bonus
+    pub fn keeper_liquidate_loan(ctx: Context<KeeperLiquidateLoan>) -> Result<()> {
+        keeper_liquidate_loan_handler(ctx)
+    }
+
+    /// lender, auctions the collateral of a liquidatable loan instead of seizing it
+    pub fn start_collateral_auction(ctx: Context<StartCollateralAuction>) -> Result<()> {
+        start_collateral_auction_handler(ctx)
+    }
+
+    /// anyone, buys the auctioned collateral at the current price
+    pub fn bid_collateral_auction(ctx: Context<BidCollateralAuction>, max_price: u64) -> Result<()> {
+        bid_collateral_auction_handler(ctx, max_price)
+    }
+
+    /// lender, takes all collateral once the auction reached its floor without a bid
+    pub fn settle_collateral_auction(ctx: Context<SettleCollateralAuction>) -> Result<()> {
+        settle_collateral_auction_handler(ctx)
     }
 }
\ No newline at end of file
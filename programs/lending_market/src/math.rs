@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+
+/// Fixed-point scale: 1.0 = 10^18
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+
+/// e in WAD
+pub const E_WAD: u128 = 2_718_281_828_459_045_235;
+
+/// Taylor series terms for `exp_wad` on the fractional part, enough for 18 decimals
+const EXP_TERMS: u128 = 24;
+
+/// `a * b / WAD` rounded down, without an intermediate `a * b` so only a result above u128 overflows
+pub fn mul_wad(a: u128, b: u128) -> Result<u128> {
+    mul_wad_rounding(a, b, false)
+}
+
+/// `a * b / WAD` rounded up
+pub fn mul_wad_up(a: u128, b: u128) -> Result<u128> {
+    mul_wad_rounding(a, b, true)
+}
+
+fn mul_wad_rounding(a: u128, b: u128, round_up: bool) -> Result<u128> {
+    // a * b / WAD = a_hi * b + a_lo * b_hi + a_lo * b_lo / WAD
+    let (a_hi, a_lo) = (a / WAD, a % WAD);
+    let (b_hi, b_lo) = (b / WAD, b % WAD);
+
+    let low = a_lo * b_lo; // both < WAD, fits in u128
+    let low = if round_up { low.div_ceil(WAD) } else { low / WAD };
+
+    a_hi.checked_mul(b)
+        .and_then(|x| x.checked_add(a_lo.checked_mul(b_hi)?))
+        .and_then(|x| x.checked_add(low))
+        .ok_or(error!(ErrorCode::MathOverflow))
+}
+
+/// `a * WAD / b` rounded up. Operands are scaled down until `b` fits in 68 bits so the remainder
+/// times WAD can't overflow, which only drops precision far below 1 WAD for growth factors
+pub fn div_wad_up(a: u128, b: u128) -> Result<u128> {
+    require!(b > 0, ErrorCode::MathOverflow);
+    let shift = (128 - b.leading_zeros()).saturating_sub(68);
+    let (a, b) = (a >> shift, b >> shift);
+
+    let (quotient, remainder) = (a / b, a % b);
+    let fraction = (remainder * WAD).div_ceil(b);
+
+    quotient.checked_mul(WAD)
+        .and_then(|x| x.checked_add(fraction))
+        .ok_or(error!(ErrorCode::MathOverflow))
+}
+
+/// `base ^ exponent` for a WAD base, by squaring
+pub fn pow_wad(base: u128, exponent: u64) -> Result<u128> {
+    let mut result = WAD;
+    let mut base = base;
+    let mut exponent = exponent;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_wad(result, base)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = mul_wad(base, base)?;
+        }
+    }
+
+    Ok(result)
+}
+
+/// `e ^ x` for a WAD exponent: integer part by squaring, fractional part by Taylor series
+pub fn exp_wad(x: u128) -> Result<u128> {
+    let whole = u64::try_from(x / WAD).map_err(|_| error!(ErrorCode::MathOverflow))?;
+    let fraction = x % WAD;
+
+    let mut term = WAD;
+    let mut sum = WAD;
+    for i in 1..=EXP_TERMS {
+        term = term * fraction / WAD / i;
+        if term == 0 {
+            break;
+        }
+        sum += term;
+    }
+
+    mul_wad(pow_wad(E_WAD, whole)?, sum)
+}
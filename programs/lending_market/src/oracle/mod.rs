@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+#[cfg(feature = "mock-oracle")]
+use crate::instructions::init_mock_price_feed::MockPriceFeed;
+use crate::error::ErrorCode;
+
+/// Pyth pull oracle receiver program, owner of `PriceUpdateV2` accounts
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// sha256("account:PriceUpdateV2")[..8]
+pub const PYTH_PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// Prices published more than 60 seconds ago are rejected
+pub const MAX_PRICE_AGE: i64 = 60;
+
+/// Prices whose confidence interval is wider than 2% of the price are rejected
+pub const MAX_CONFIDENCE_BPS: u128 = 200;
+
+/// Loan and collateral values are expressed in a common quote unit with 6 decimals (USD-like)
+pub const VALUE_DECIMALS: i32 = 6;
+
+#[derive(AnchorDeserialize)]
+enum PythVerificationLevel {
+    Partial { _num_signatures: u8 },
+    Full,
+}
+
+/// Leading fields of a Pyth `PriceUpdateV2` account (after the discriminator)
+#[derive(AnchorDeserialize)]
+struct PythPriceUpdateV2 {
+    _write_authority: Pubkey,
+    verification_level: PythVerificationLevel,
+    feed_id: [u8; 32],
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    publish_time: i64,
+}
+
+/// Positive price of one whole token: `price * 10^expo` quote units
+#[derive(Clone, Copy, Debug)]
+pub struct OraclePrice {
+    pub price: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    /// Read a price from a Pyth `PriceUpdateV2` account, or a program-owned `MockPriceFeed`
+    /// when built with the `mock-oracle` feature. The account must carry `feed_id`
+    /// (the Pyth feed id, or the mint for a mock feed).
+    pub fn read(feed: &AccountInfo, feed_id: &[u8; 32]) -> Result<Self> {
+        let data = feed.try_borrow_data()?;
+
+        let (price, expo, publish_time) = match feed.owner {
+            #[cfg(feature = "mock-oracle")]
+            owner if owner == &crate::ID => {
+                let mock = MockPriceFeed::try_deserialize(&mut &data[..])
+                    .map_err(|_| error!(ErrorCode::InvalidPriceFeed))?;
+                require!(mock.mint.to_bytes() == *feed_id, ErrorCode::InvalidPriceFeed);
+                (mock.price, mock.expo, mock.publish_time)
+            }
+            owner if owner == &PYTH_RECEIVER_PROGRAM_ID => {
+                require!(
+                    data.len() > 8 && data[..8] == PYTH_PRICE_UPDATE_V2_DISCRIMINATOR,
+                    ErrorCode::InvalidPriceFeed
+                );
+                let update = PythPriceUpdateV2::deserialize(&mut &data[8..])
+                    .map_err(|_| error!(ErrorCode::InvalidPriceFeed))?;
+                require!(
+                    matches!(update.verification_level, PythVerificationLevel::Full),
+                    ErrorCode::InvalidPriceFeed
+                );
+                require!(update.feed_id == *feed_id, ErrorCode::InvalidPriceFeed);
+                require!(
+                    (update.conf as u128) * 10000
+                        <= (update.price.unsigned_abs() as u128) * MAX_CONFIDENCE_BPS,
+                    ErrorCode::PriceConfidenceTooWide
+                );
+                (update.price, update.exponent, update.publish_time)
+            }
+            _ => return err!(ErrorCode::InvalidPriceFeed),
+        };
+
+        require!(price > 0, ErrorCode::InvalidPrice);
+
+        Ok(Self {
+            price: price as u64,
+            expo,
+            publish_time,
+        })
+    }
+
+    /// Read a price and reject it if it is older than `MAX_PRICE_AGE`
+    pub fn load(feed: &AccountInfo, feed_id: &[u8; 32], current_time: i64) -> Result<Self> {
+        let price = Self::read(feed, feed_id)?;
+        require!(
+            current_time.saturating_sub(price.publish_time) <= MAX_PRICE_AGE,
+            ErrorCode::StalePrice
+        );
+        Ok(price)
+    }
+
+    /// Value of `amount` base units of a token with `decimals` decimals, in quote units
+    pub fn value(&self, amount: u64, decimals: u8) -> Result<u128> {
+        let raw = (amount as u128)
+            .checked_mul(self.price as u128)
+            .ok_or(error!(ErrorCode::PriceCalculationOverflow))?;
+
+        let exponent = self.expo + VALUE_DECIMALS - decimals as i32;
+        if exponent >= 0 {
+            raw.checked_mul(pow10(exponent as u32)?)
+                .ok_or(error!(ErrorCode::PriceCalculationOverflow))
+        } else {
+            Ok(raw / pow10(exponent.unsigned_abs())?)
+        }
+    }
+
+    /// Base units of a token with `decimals` decimals worth at least `value` quote units (rounds up)
+    pub fn amount_for_value(&self, value: u128, decimals: u8) -> Result<u64> {
+        let exponent = self.expo + VALUE_DECIMALS - decimals as i32;
+        let (numerator, denominator) = if exponent >= 0 {
+            let denominator = (self.price as u128)
+                .checked_mul(pow10(exponent as u32)?)
+                .ok_or(error!(ErrorCode::PriceCalculationOverflow))?;
+            (value, denominator)
+        } else {
+            let numerator = value
+                .checked_mul(pow10(exponent.unsigned_abs())?)
+                .ok_or(error!(ErrorCode::PriceCalculationOverflow))?;
+            (numerator, self.price as u128)
+        };
+
+        u64::try_from(numerator.div_ceil(denominator))
+            .map_err(|_| error!(ErrorCode::PriceCalculationOverflow))
+    }
+}
+
+fn pow10(exponent: u32) -> Result<u128> {
+    10u128
+        .checked_pow(exponent)
+        .ok_or(error!(ErrorCode::PriceCalculationOverflow))
+}
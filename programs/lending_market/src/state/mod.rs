@@ -1,20 +1,49 @@
 use anchor_lang::prelude::*;
+use crate::instructions::create_asset_pair_market::AssetPairMarket;
+use crate::oracle::OraclePrice;
+use crate::math::{self, WAD};
 use crate::error::ErrorCode;
 
+pub mod orderbook;
+pub use orderbook::*;
+
+/// How interest grows on a loan, chosen by the lender on the offer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum InterestMode {
+    /// Interest on principal only
+    Simple,
+    /// Compounds once a day, linear within the day
+    CompoundDaily,
+    /// Continuous compounding, e^(rate * time)
+    CompoundContinuous,
+    /// Simple interest at the pair's utilization-based rate, which moves with every borrow and repay
+    Variable,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Loan {
-    pub lending_offer: Pubkey,
+    pub lending_offer: Pubkey,    // offer, or borrow request for loans opened by fill_borrow_request
+    pub asset_pair_market: Pubkey,
     pub lender: Pubkey,
     pub borrower: Pubkey,
     pub principal_amount: u64,
     pub collateral_amount: u64,
-    pub interest_rate_bps: u64,   // locked from offer
+    pub interest_rate_bps: u64,   // locked from offer, unused for InterestMode::Variable
+    pub interest_mode: InterestMode,  // locked from offer
+    pub penalty_rate_bps: u64,        // locked from offer, charged on top past the due time
+    pub notice_duration: i64,         // locked from offer, seconds from request_repayment to the deadline
     pub ltv_bps: u64,              // locked from offer
+    pub origination_loan_value: u64,        // quote units (oracle::VALUE_DECIMALS) at take_loan
+    pub origination_collateral_value: u64,  // quote units (oracle::VALUE_DECIMALS) at take_loan
     pub loan_start_time: i64,
-    pub last_interest_update: i64,
+    pub last_interest_update: i64,   // checkpoint, interest before it is stored in accrued_interest
+    pub accrued_interest: u64,       // unpaid interest up to last_interest_update
+    pub last_rate_index: u128,       // pair rate_index at last_interest_update, for InterestMode::Variable
+    #[max_len(1)]
+    pub repayment_deadline: Option<i64>,  // request time + notice_duration
     #[max_len(1)]
-    pub repayment_deadline: Option<i64>,  // 48hr notice
+    pub maturity: Option<i64>,            // fixed-term loans: start + offer duration, None for perpetual
     pub is_active: bool,
     pub bump: u8,
 }
@@ -22,44 +51,180 @@ pub struct Loan {
 impl Loan {
     pub const SEED: &'static [u8] = b"loan";
     pub const COLLATERAL_SEED: &'static [u8] = b"collateral";
-    pub const REPAYMENT_NOTICE_DURATION: i64 = 48 * 60 * 60; // 48 hours in seconds
+    pub const SECONDS_PER_YEAR: i64 = 365 * 24 * 60 * 60;
+    pub const LIQUIDATION_LTV_BPS: u64 = 12000; // 120%, default and maximum for AssetPairMarket::liquidation_ltv_bps
+    pub const MATURITY_GRACE_PERIOD: i64 = 24 * 60 * 60; // fixed-term loans are due 1 day after maturity
 
-    /// Calculate the current interest owed
-    pub fn calculate_interest(&self, current_time: i64) -> Result<u64> {
-        let time_elapsed = current_time.saturating_sub(self.loan_start_time);
-        let days_elapsed = time_elapsed / 86400; // Convert seconds to days
+    /// When the loan must be repaid: the repayment deadline or the end of the maturity grace period,
+    /// whichever comes first
+    pub fn due_time(&self) -> Option<i64> {
+        let matured = self.maturity.map(|maturity| maturity + Self::MATURITY_GRACE_PERIOD);
+        match (self.repayment_deadline, matured) {
+            (Some(deadline), Some(matured)) => Some(deadline.min(matured)),
+            (deadline, matured) => deadline.or(matured),
+        }
+    }
+
+    /// Interest accrued since `last_interest_update`, per second and rounded up in the lender's favor.
+    /// Compounding modes also charge interest on `accrued_interest`. Time past the due time
+    /// additionally accrues the penalty segment.
+    fn interest_since_checkpoint(&self, current_time: i64, asset_pair_market: &AssetPairMarket) -> Result<u64> {
+        let time_elapsed = current_time.saturating_sub(self.last_interest_update).max(0);
+
+        let normal_interest = match self.interest_mode {
+            // interest = principal * (rate_bps/10000) * (seconds_elapsed/seconds_per_year)
+            InterestMode::Simple => (self.principal_amount as u128)
+                .checked_mul(self.interest_rate_bps as u128)
+                .ok_or(error!(ErrorCode::InterestCalculationOverflow))?
+                .checked_mul(time_elapsed as u128)
+                .ok_or(error!(ErrorCode::InterestCalculationOverflow))?
+                .div_ceil(10000 * Self::SECONDS_PER_YEAR as u128),
+            // interest = (principal + accrued) * (growth - 1)
+            InterestMode::CompoundDaily | InterestMode::CompoundContinuous => {
+                let base = self.principal_amount as u128 + self.accrued_interest as u128;
+                let growth = self.growth_factor(current_time)?;
+                math::mul_wad_up(base, growth.saturating_sub(WAD))?
+            }
+            // interest = principal * integral of the pair's rate since the checkpoint
+            InterestMode::Variable => {
+                let rate_integral = asset_pair_market
+                    .current_rate_index(current_time)?
+                    .saturating_sub(self.last_rate_index);
+                math::mul_wad_up(self.principal_amount as u128, rate_integral)?
+            }
+        };
+
+        let interest = normal_interest
+            .checked_add(self.penalty_interest_since_checkpoint(current_time)?)
+            .ok_or(error!(ErrorCode::InterestCalculationOverflow))?;
+
+        u64::try_from(interest).map_err(|_| error!(ErrorCode::InterestCalculationOverflow))
+    }
+
+    /// Penalty segment of the checkpoint period: `penalty_rate_bps` simple interest on principal
+    /// for the seconds after `due_time`, rounded up in the lender's favor
+    fn penalty_interest_since_checkpoint(&self, current_time: i64) -> Result<u128> {
+        let Some(deadline) = self.due_time() else {
+            return Ok(0);
+        };
+        let penalty_start = self.last_interest_update.max(deadline);
+        let time_past_deadline = current_time.saturating_sub(penalty_start).max(0);
 
-        // interest = principal * (rate_bps/10000) * (days_elapsed/365)
-        let interest = (self.principal_amount as u128)
-            .checked_mul(self.interest_rate_bps as u128)
+        Ok((self.principal_amount as u128)
+            .checked_mul(self.penalty_rate_bps as u128)
             .ok_or(error!(ErrorCode::InterestCalculationOverflow))?
-            .checked_mul(days_elapsed as u128)
+            .checked_mul(time_past_deadline as u128)
             .ok_or(error!(ErrorCode::InterestCalculationOverflow))?
-            .checked_div(10000 * 365)
-            .ok_or(error!(ErrorCode::InterestCalculationOverflow))?;
+            .div_ceil(10000 * Self::SECONDS_PER_YEAR as u128))
+    }
+
+    /// Growth of a compounding balance from `last_interest_update` to `current_time`, in WAD
+    fn growth_factor(&self, current_time: i64) -> Result<u128> {
+        let annual_rate = (self.interest_rate_bps as u128)
+            .checked_mul(WAD)
+            .ok_or(error!(ErrorCode::MathOverflow))?
+            / 10000;
+
+        match self.interest_mode {
+            // Whole days are counted from loan_start_time, so a checkpoint doesn't restart the
+            // compounding period: growth = G(now) / G(checkpoint), G measured from the loan start
+            InterestMode::CompoundDaily => {
+                let daily_rate = annual_rate / 365;
+                let growth_since_start = |time: i64| -> Result<u128> {
+                    let seconds = time.saturating_sub(self.loan_start_time).max(0) as u128;
+                    let days = u64::try_from(seconds / 86400).map_err(|_| error!(ErrorCode::MathOverflow))?;
+                    let partial_day = daily_rate
+                        .checked_mul(seconds % 86400)
+                        .ok_or(error!(ErrorCode::MathOverflow))?
+                        / 86400;
+                    math::mul_wad(math::pow_wad(WAD + daily_rate, days)?, WAD + partial_day)
+                };
+                math::div_wad_up(
+                    growth_since_start(current_time)?,
+                    growth_since_start(self.last_interest_update)?,
+                )
+            }
+            InterestMode::Simple | InterestMode::Variable | InterestMode::CompoundContinuous => {
+                let time_elapsed = current_time.saturating_sub(self.last_interest_update).max(0) as u128;
+                let rate_times_time = annual_rate
+                    .checked_mul(time_elapsed)
+                    .ok_or(error!(ErrorCode::MathOverflow))?
+                    / Self::SECONDS_PER_YEAR as u128;
+                if self.interest_mode == InterestMode::CompoundContinuous {
+                    math::exp_wad(rate_times_time)
+                } else {
+                    WAD.checked_add(rate_times_time).ok_or(error!(ErrorCode::MathOverflow))
+                }
+            }
+        }
+    }
 
-        Ok(interest as u64)
+    /// Calculate the current interest owed
+    pub fn calculate_interest(&self, current_time: i64, asset_pair_market: &AssetPairMarket) -> Result<u64> {
+        self.accrued_interest
+            .checked_add(self.interest_since_checkpoint(current_time, asset_pair_market)?)
+            .ok_or(error!(ErrorCode::InterestCalculationOverflow))
+    }
+
+    /// Store interest owed so far in `accrued_interest` and move the checkpoint to `current_time`,
+    /// must run before `principal_amount` changes
+    pub fn accrue_interest(&mut self, current_time: i64, asset_pair_market: &AssetPairMarket) -> Result<()> {
+        self.accrued_interest = self.calculate_interest(current_time, asset_pair_market)?;
+        self.last_interest_update = current_time;
+        self.last_rate_index = asset_pair_market.current_rate_index(current_time)?;
+        Ok(())
     }
 
-    /// Calculate total repayment amount (principal + interest)
-    pub fn calculate_repayment_amount(&self, current_time: i64) -> Result<u64> {
-        let interest = self.calculate_interest(current_time)?;
+    /// Calculate total repayment amount (principal + interest, penalty interest included)
+    pub fn calculate_repayment_amount(&self, current_time: i64, asset_pair_market: &AssetPairMarket) -> Result<u64> {
+        let interest = self.calculate_interest(current_time, asset_pair_market)?;
         self.principal_amount
             .checked_add(interest)
             .ok_or(error!(ErrorCode::InterestCalculationOverflow))
     }
 
+    /// Current LTV in bps: value of the debt (principal + interest) over value of the collateral
+    pub fn current_ltv_bps(
+        &self,
+        current_time: i64,
+        asset_pair_market: &AssetPairMarket,
+        loan_price: &OraclePrice,
+        collateral_price: &OraclePrice,
+    ) -> Result<u64> {
+        let debt = self.calculate_repayment_amount(current_time, asset_pair_market)?;
+        asset_pair_market.ltv_bps(debt, self.collateral_amount, loan_price, collateral_price)
+    }
+
     /// Check if loan can be liquidated
-    pub fn can_liquidate(&self, current_time: i64, current_ltv_bps: u64) -> bool {
+    pub fn can_liquidate(
+        &self,
+        current_time: i64,
+        asset_pair_market: &AssetPairMarket,
+        loan_price: &OraclePrice,
+        collateral_price: &OraclePrice,
+    ) -> Result<bool> {
         // Can liquidate if:
-        // 1. Repayment deadline has passed
-        if let Some(deadline) = self.repayment_deadline {
+        // 1. Repayment deadline or maturity grace period has passed
+        if let Some(deadline) = self.due_time() {
             if current_time > deadline {
-                return true;
+                return Ok(true);
             }
         }
 
-        // 2. LTV exceeds 120% (12000 bps)
-        current_ltv_bps > 12000
+        // 2. LTV exceeds the pair's liquidation threshold at oracle prices
+        self.is_undercollateralized(current_time, asset_pair_market, loan_price, collateral_price)
+    }
+
+    /// Oracle-priced LTV exceeds the pair's `liquidation_ltv_bps`
+    pub fn is_undercollateralized(
+        &self,
+        current_time: i64,
+        asset_pair_market: &AssetPairMarket,
+        loan_price: &OraclePrice,
+        collateral_price: &OraclePrice,
+    ) -> Result<bool> {
+        let current_ltv_bps =
+            self.current_ltv_bps(current_time, asset_pair_market, loan_price, collateral_price)?;
+        Ok(current_ltv_bps > asset_pair_market.liquidation_ltv_bps)
     }
 }
\ No newline at end of file
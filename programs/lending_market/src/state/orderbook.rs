@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+/// Offers indexed per pair, the account stays below the 10KB CPI allocation limit
+pub const MAX_ORDERBOOK_ENTRIES: usize = 128;
+
+/// One active fixed-rate offer on the book
+#[zero_copy]
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct OrderbookEntry {
+    pub lending_offer: Pubkey,
+    pub interest_rate_bps: u64,
+    pub ltv_bps: u64,
+    pub remaining_amount: u64,
+    pub min_fill_amount: u64,
+}
+
+impl OrderbookEntry {
+    /// Cheapest rate first, then the highest LTV, ties keep insertion order
+    fn ranks_before(&self, other: &OrderbookEntry) -> bool {
+        self.interest_rate_bps < other.interest_rate_bps
+            || (self.interest_rate_bps == other.interest_rate_bps && self.ltv_bps > other.ltv_bps)
+    }
+}
+
+/// Sorted index of the best active offers of an AssetPairMarket, so the top of book can be read from
+/// one account. Variable-rate offers are not indexed, their rate moves with utilization.
+/// seeds = [Orderbook::SEED, asset_pair_market.key().as_ref()]
+#[account(zero_copy)]
+pub struct Orderbook {
+    pub asset_pair_market: Pubkey,
+    pub len: u64,
+    pub entries: [OrderbookEntry; MAX_ORDERBOOK_ENTRIES],
+}
+
+impl Orderbook {
+    pub const SEED: &'static [u8] = b"orderbook";
+
+    /// Active entries, best first
+    pub fn entries(&self) -> &[OrderbookEntry] {
+        &self.entries[..self.len as usize]
+    }
+
+    /// Whether an offer is on the book
+    pub fn contains(&self, lending_offer: &Pubkey) -> bool {
+        self.entries().iter().any(|entry| entry.lending_offer == *lending_offer)
+    }
+
+    /// Insert in rank order. When the book is full the worst entry is dropped to make room,
+    /// an offer ranking below every entry of a full book is not indexed
+    pub fn insert(&mut self, entry: OrderbookEntry) {
+        let mut len = self.len as usize;
+        let position = self
+            .entries()
+            .iter()
+            .position(|existing| entry.ranks_before(existing))
+            .unwrap_or(len);
+        if len == MAX_ORDERBOOK_ENTRIES {
+            if position == len {
+                return;
+            }
+            len -= 1;
+        }
+        self.entries.copy_within(position..len, position + 1);
+        self.entries[position] = entry;
+        self.len = (len + 1) as u64;
+    }
+
+    /// Remove an offer, no-op if it is not on the book
+    pub fn remove(&mut self, lending_offer: &Pubkey) {
+        let len = self.len as usize;
+        if let Some(position) = self.entries().iter().position(|entry| entry.lending_offer == *lending_offer) {
+            self.entries.copy_within(position + 1..len, position);
+            self.entries[len - 1] = OrderbookEntry::default();
+            self.len -= 1;
+        }
+    }
+
+    /// Replace an offer's entry after its terms changed. It keeps its queue position when the rate and LTV
+    /// are unchanged, otherwise it is re-ranked (or indexed, if it wasn't on the book)
+    pub fn update_entry(&mut self, entry: OrderbookEntry) {
+        let len = self.len as usize;
+        if let Some(existing) = self.entries[..len].iter_mut().find(|existing| existing.lending_offer == entry.lending_offer) {
+            if existing.interest_rate_bps == entry.interest_rate_bps && existing.ltv_bps == entry.ltv_bps {
+                *existing = entry;
+                return;
+            }
+        }
+        self.remove(&entry.lending_offer);
+        self.insert(entry);
+    }
+
+    /// Track a fill, the offer leaves the book once nothing remains
+    pub fn update_remaining(&mut self, lending_offer: &Pubkey, remaining_amount: u64) {
+        if remaining_amount == 0 {
+            self.remove(lending_offer);
+            return;
+        }
+        let len = self.len as usize;
+        if let Some(entry) = self.entries[..len].iter_mut().find(|entry| entry.lending_offer == *lending_offer) {
+            entry.remaining_amount = remaining_amount;
+        }
+    }
+}
@@ -7,6 +7,8 @@ use solana_sdk::{
     signature::{Keypair, Signer},
     transaction::Transaction,
 };
+use lending_market::instructions::{AssetPairMarket, CollateralAuction, LendingMarket, LendingOffer, RateModel};
+use lending_market::state::{InterestMode, Loan, MAX_ORDERBOOK_ENTRIES};
 use utils::*;
 
 // Constants
@@ -15,9 +17,6 @@ const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
 // System program ID
 const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::new_from_array([0u8; 32]);
 
-// Import the lending_market program
-use lending_market;
-
 
 #[test]
 fn test_init_lending_market() {
@@ -99,6 +98,178 @@ fn test_init_lending_market() {
     }
 }
 
+#[test]
+fn test_update_lending_market() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let attacker = Keypair::new();
+    let fee_recipient = Keypair::new();
+    let new_fee_recipient = Keypair::new();
+
+    svm.airdrop(&admin.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&attacker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+    let lending_market_pda = init_lending_market(&mut svm, &admin, &fee_recipient.pubkey(), 200, 100);
+
+    let update_ix = |signer: &Pubkey,
+                     fee_recipient: Option<Pubkey>,
+                     lender_fee_bps: Option<u64>,
+                     borrower_fee_bps: Option<u64>| {
+        let mut data = Vec::new();
+        data.extend_from_slice(&anchor_discriminator("global", "update_lending_market"));
+        match fee_recipient {
+            Some(key) => {
+                data.push(1);
+                data.extend_from_slice(&key.to_bytes());
+            }
+            None => data.push(0),
+        }
+        for bps in [lender_fee_bps, borrower_fee_bps] {
+            match bps {
+                Some(value) => {
+                    data.push(1);
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+                None => data.push(0),
+            }
+        }
+        data.extend_from_slice(&[0, 0, 0]); // guardian, min_notice_duration, max_notice_duration: None
+
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*signer, true),
+                AccountMeta::new(lending_market_pda, false),
+            ],
+            data,
+        }
+    };
+
+    // Non-admin cannot update
+    let result = send_ix(&mut svm, update_ix(&attacker.pubkey(), None, Some(0), None), &attacker, &[]);
+    assert!(result.is_err(), "Non-admin update should fail");
+
+    // Fees above the protocol bounds are rejected
+    let result = send_ix(&mut svm, update_ix(&admin.pubkey(), None, Some(1001), None), &admin, &[]);
+    assert!(result.is_err(), "Lender fee above 10% should fail");
+    let result = send_ix(&mut svm, update_ix(&admin.pubkey(), None, None, Some(501)), &admin, &[]);
+    assert!(result.is_err(), "Borrower fee above 5% should fail");
+
+    // Update only the lender fee
+    send_ix(&mut svm, update_ix(&admin.pubkey(), None, Some(300), None), &admin, &[])
+        .expect("Admin update should succeed");
+
+    let market: LendingMarket = get_account(&svm, &lending_market_pda);
+    assert_eq!(market.fee_recipient.to_bytes(), fee_recipient.pubkey().to_bytes());
+    assert_eq!(market.lender_fee_bps, 300);
+    assert_eq!(market.borrower_fee_bps, 100);
+
+    // Update fee recipient and borrower fee, lender fee unchanged
+    send_ix(
+        &mut svm,
+        update_ix(&admin.pubkey(), Some(new_fee_recipient.pubkey()), None, Some(50)),
+        &admin,
+        &[],
+    )
+    .expect("Admin update should succeed");
+
+    let market: LendingMarket = get_account(&svm, &lending_market_pda);
+    assert_eq!(market.fee_recipient.to_bytes(), new_fee_recipient.pubkey().to_bytes());
+    assert_eq!(market.lender_fee_bps, 300);
+    assert_eq!(market.borrower_fee_bps, 50);
+
+    println!(" Lending market configuration updated");
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let new_admin = Keypair::new();
+    let attacker = Keypair::new();
+    let fee_recipient = Keypair::new();
+
+    svm.airdrop(&admin.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&new_admin.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&attacker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+    let lending_market_pda = init_lending_market(&mut svm, &admin, &fee_recipient.pubkey(), 200, 100);
+
+    let propose_ix = |signer: &Pubkey, new_admin: Option<Pubkey>| {
+        let mut data = Vec::new();
+        data.extend_from_slice(&anchor_discriminator("global", "propose_admin"));
+        match new_admin {
+            Some(key) => {
+                data.push(1);
+                data.extend_from_slice(&key.to_bytes());
+            }
+            None => data.push(0),
+        }
+
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*signer, true),
+                AccountMeta::new(lending_market_pda, false),
+            ],
+            data,
+        }
+    };
+
+    let accept_ix = |signer: &Pubkey| Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*signer, true),
+            AccountMeta::new(lending_market_pda, false),
+        ],
+        data: anchor_discriminator("global", "accept_admin").to_vec(),
+    };
+
+    // Only the admin can propose
+    let result = send_ix(&mut svm, propose_ix(&attacker.pubkey(), Some(attacker.pubkey())), &attacker, &[]);
+    assert!(result.is_err(), "Non-admin proposal should fail");
+
+    // Nothing to accept before a proposal
+    let result = send_ix(&mut svm, accept_ix(&new_admin.pubkey()), &new_admin, &[]);
+    assert!(result.is_err(), "Accept without proposal should fail");
+
+    send_ix(&mut svm, propose_ix(&admin.pubkey(), Some(new_admin.pubkey())), &admin, &[])
+        .expect("Admin proposal should succeed");
+
+    let market: LendingMarket = get_account(&svm, &lending_market_pda);
+    assert_eq!(market.admin.to_bytes(), admin.pubkey().to_bytes(), "Admin unchanged until accepted");
+    assert_eq!(market.pending_admin.map(|key| key.to_bytes()), Some(new_admin.pubkey().to_bytes()));
+
+    // Only the proposed key can accept
+    let result = send_ix(&mut svm, accept_ix(&attacker.pubkey()), &attacker, &[]);
+    assert!(result.is_err(), "Accept by other key should fail");
+
+    send_ix(&mut svm, accept_ix(&new_admin.pubkey()), &new_admin, &[])
+        .expect("Pending admin accept should succeed");
+
+    let market: LendingMarket = get_account(&svm, &lending_market_pda);
+    assert_eq!(market.admin.to_bytes(), new_admin.pubkey().to_bytes());
+    assert!(market.pending_admin.is_none());
+
+    // Old admin has lost control
+    let result = send_ix(&mut svm, propose_ix(&admin.pubkey(), Some(admin.pubkey())), &admin, &[]);
+    assert!(result.is_err(), "Old admin proposal should fail");
+
+    println!(" Admin transferred to {}", new_admin.pubkey());
+}
+
 #[test]
 fn test_create_asset_pair_market() {
     // Create the test environment
@@ -150,23 +321,41 @@ fn test_create_asset_pair_market() {
     println!(" Created collateral mint: {}", collateral_mint);
 
     // Step 3: Create asset pair market
+    // Register mock oracle prices for both mints
+    let loan_price_feed = create_mock_price_feed(&mut svm, &admin, &loan_mint, USDC_PRICE, PRICE_EXPO);
+    let collateral_price_feed = create_mock_price_feed(&mut svm, &admin, &collateral_mint, SOL_PRICE, PRICE_EXPO);
+
     let (asset_pair_market_pda, _) = get_pda_asset_pair_market(&loan_mint, &collateral_mint);
+    let (orderbook_pda, _) = get_pda_orderbook(&asset_pair_market_pda);
 
     let mut create_pair_data = Vec::new();
     create_pair_data.extend_from_slice(&anchor_discriminator("global", "create_asset_pair_market"));
-
-    let create_pair_ix = Instruction {
+    create_pair_data.extend_from_slice(&loan_mint.to_bytes()); // loan_feed_id: a mock feed is identified by its mint
+    create_pair_data.extend_from_slice(&collateral_mint.to_bytes()); // collateral_feed_id
+
+    // Feeds that don't carry the expected feed ids are rejected
+    let mut swapped_feed_data = anchor_discriminator("global", "create_asset_pair_market").to_vec();
+    swapped_feed_data.extend_from_slice(&collateral_mint.to_bytes());
+    swapped_feed_data.extend_from_slice(&loan_mint.to_bytes());
+    let mut swapped_feed_ix = Instruction {
         program_id,
         accounts: vec![
             AccountMeta::new(admin.pubkey(), true),
             AccountMeta::new_readonly(lending_market_pda, false),
             AccountMeta::new(asset_pair_market_pda, false),
+            AccountMeta::new(orderbook_pda, false),
             AccountMeta::new_readonly(loan_mint, false),
             AccountMeta::new_readonly(collateral_mint, false),
+            AccountMeta::new_readonly(loan_price_feed, false),
+            AccountMeta::new_readonly(collateral_price_feed, false),
             AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
         ],
-        data: create_pair_data,
+        data: swapped_feed_data,
     };
+    assert!(send_ix(&mut svm, swapped_feed_ix.clone(), &admin, &[]).is_err(), "Mismatched feed ids should fail");
+
+    swapped_feed_ix.data = create_pair_data;
+    let create_pair_ix = swapped_feed_ix;
 
     let tx = Transaction::new_signed_with_payer(
         &[create_pair_ix],
@@ -244,10 +433,17 @@ fn test_create_and_cancel_lending_offer() {
     mint_tokens(&mut svm, &loan_mint, &lender_loan_account, &admin, loan_amount);
 
     // Create asset pair market
+    // Register mock oracle prices for both mints
+    let loan_price_feed = create_mock_price_feed(&mut svm, &admin, &loan_mint, USDC_PRICE, PRICE_EXPO);
+    let collateral_price_feed = create_mock_price_feed(&mut svm, &admin, &collateral_mint, SOL_PRICE, PRICE_EXPO);
+
     let (asset_pair_market_pda, _) = get_pda_asset_pair_market(&loan_mint, &collateral_mint);
+    let (orderbook_pda, _) = get_pda_orderbook(&asset_pair_market_pda);
 
     let mut create_pair_data = Vec::new();
     create_pair_data.extend_from_slice(&anchor_discriminator("global", "create_asset_pair_market"));
+    create_pair_data.extend_from_slice(&loan_mint.to_bytes()); // loan_feed_id: a mock feed is identified by its mint
+    create_pair_data.extend_from_slice(&collateral_mint.to_bytes()); // collateral_feed_id
 
     let create_pair_ix = Instruction {
         program_id,
@@ -255,8 +451,11 @@ fn test_create_and_cancel_lending_offer() {
             AccountMeta::new(admin.pubkey(), true),
             AccountMeta::new_readonly(lending_market_pda, false),
             AccountMeta::new(asset_pair_market_pda, false),
+            AccountMeta::new(orderbook_pda, false),
             AccountMeta::new_readonly(loan_mint, false),
             AccountMeta::new_readonly(collateral_mint, false),
+            AccountMeta::new_readonly(loan_price_feed, false),
+            AccountMeta::new_readonly(collateral_price_feed, false),
             AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
         ],
         data: create_pair_data,
@@ -287,12 +486,20 @@ fn test_create_and_cancel_lending_offer() {
     create_offer_data.extend_from_slice(&loan_amount.to_le_bytes());
     create_offer_data.extend_from_slice(&1000u64.to_le_bytes()); // 10% APR
     create_offer_data.extend_from_slice(&8000u64.to_le_bytes()); // 80% LTV
+    create_offer_data.extend_from_slice(&0u64.to_le_bytes()); // no minimum fill
+    create_offer_data.push(0); // simple interest
+    create_offer_data.extend_from_slice(&0u64.to_le_bytes()); // no penalty rate
+    create_offer_data.extend_from_slice(&(48i64 * 60 * 60).to_le_bytes()); // 48h notice
+    create_offer_data.push(0); // perpetual
+    create_offer_data.push(0); // no expiry
 
     let create_offer_ix = Instruction {
         program_id,
         accounts: vec![
             AccountMeta::new(lender.pubkey(), true),
-            AccountMeta::new_readonly(asset_pair_market_pda, false),
+            AccountMeta::new_readonly(lending_market_pda, false),
+            AccountMeta::new(asset_pair_market_pda, false),
+            AccountMeta::new(orderbook_pda, false),
             AccountMeta::new(lending_offer_pda, false),
             AccountMeta::new(escrow_pda, false),
             AccountMeta::new_readonly(loan_mint, false),
@@ -334,6 +541,8 @@ fn test_create_and_cancel_lending_offer() {
         accounts: vec![
             AccountMeta::new(lender.pubkey(), true),
             AccountMeta::new(lending_offer_pda, false),
+            AccountMeta::new(asset_pair_market_pda, false),
+            AccountMeta::new(orderbook_pda, false),
             AccountMeta::new(escrow_pda, false),
             AccountMeta::new(lender_loan_account, false),
             AccountMeta::new_readonly(spl_token::id(), false),
@@ -422,7 +631,8 @@ fn test_full_lending_flow_with_fees() {
 
     // Mint tokens
     let loan_amount = 1000_000000; // 1000 USDC
-    let collateral_amount = 1_250_000000000; // 1.25 SOL worth (for 80% LTV)
+    // 1000 USDC at $1 with 80% LTV requires $1250 of collateral = 12.5 SOL at $100
+    let collateral_amount = 12_500_000_000; // 12.5 SOL (9 decimals)
 
     mint_tokens(&mut svm, &loan_mint, &lender_loan_account, &admin, loan_amount);
     mint_tokens(&mut svm, &collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
@@ -430,10 +640,17 @@ fn test_full_lending_flow_with_fees() {
     mint_tokens(&mut svm, &loan_mint, &borrower_loan_account, &admin, loan_amount * 2);
 
     // Create asset pair market
+    // Register mock oracle prices for both mints
+    let loan_price_feed = create_mock_price_feed(&mut svm, &admin, &loan_mint, USDC_PRICE, PRICE_EXPO);
+    let collateral_price_feed = create_mock_price_feed(&mut svm, &admin, &collateral_mint, SOL_PRICE, PRICE_EXPO);
+
     let (asset_pair_market_pda, _) = get_pda_asset_pair_market(&loan_mint, &collateral_mint);
+    let (orderbook_pda, _) = get_pda_orderbook(&asset_pair_market_pda);
 
     let mut create_pair_data = Vec::new();
     create_pair_data.extend_from_slice(&anchor_discriminator("global", "create_asset_pair_market"));
+    create_pair_data.extend_from_slice(&loan_mint.to_bytes()); // loan_feed_id: a mock feed is identified by its mint
+    create_pair_data.extend_from_slice(&collateral_mint.to_bytes()); // collateral_feed_id
 
     let create_pair_ix = Instruction {
         program_id,
@@ -441,8 +658,11 @@ fn test_full_lending_flow_with_fees() {
             AccountMeta::new(admin.pubkey(), true),
             AccountMeta::new_readonly(lending_market_pda, false),
             AccountMeta::new(asset_pair_market_pda, false),
+            AccountMeta::new(orderbook_pda, false),
             AccountMeta::new_readonly(loan_mint, false),
             AccountMeta::new_readonly(collateral_mint, false),
+            AccountMeta::new_readonly(loan_price_feed, false),
+            AccountMeta::new_readonly(collateral_price_feed, false),
             AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
         ],
         data: create_pair_data,
@@ -468,12 +688,20 @@ fn test_full_lending_flow_with_fees() {
     create_offer_data.extend_from_slice(&loan_amount.to_le_bytes());
     create_offer_data.extend_from_slice(&1000u64.to_le_bytes()); // 10% APR
     create_offer_data.extend_from_slice(&8000u64.to_le_bytes()); // 80% LTV
+    create_offer_data.extend_from_slice(&0u64.to_le_bytes()); // no minimum fill
+    create_offer_data.push(0); // simple interest
+    create_offer_data.extend_from_slice(&0u64.to_le_bytes()); // no penalty rate
+    create_offer_data.extend_from_slice(&(48i64 * 60 * 60).to_le_bytes()); // 48h notice
+    create_offer_data.push(0); // perpetual
+    create_offer_data.push(0); // no expiry
 
     let create_offer_ix = Instruction {
         program_id,
         accounts: vec![
             AccountMeta::new(lender.pubkey(), true),
-            AccountMeta::new_readonly(asset_pair_market_pda, false),
+            AccountMeta::new_readonly(lending_market_pda, false),
+            AccountMeta::new(asset_pair_market_pda, false),
+            AccountMeta::new(orderbook_pda, false),
             AccountMeta::new(lending_offer_pda, false),
             AccountMeta::new(escrow_pda, false),
             AccountMeta::new_readonly(loan_mint, false),
@@ -506,40 +734,63 @@ fn test_full_lending_flow_with_fees() {
     println!("Borrower fee (1%): {} USDC", loan_amount / 1_000_000 / 100);
     println!("Borrower receives: {} USDC", loan_amount * 99 / 100 / 1_000_000);
 
-    let mut take_loan_data = Vec::new();
-    take_loan_data.extend_from_slice(&anchor_discriminator("global", "take_loan"));
-    take_loan_data.extend_from_slice(&collateral_amount.to_le_bytes());
-
-    let take_loan_ix = Instruction {
-        program_id,
-        accounts: vec![
-            AccountMeta::new(borrower.pubkey(), true),
-            AccountMeta::new_readonly(lending_market_pda, false),
-            AccountMeta::new_readonly(asset_pair_market_pda, false),
-            AccountMeta::new(lending_offer_pda, false),
-            AccountMeta::new(loan_pda, false),
-            AccountMeta::new(escrow_pda, false),
-            AccountMeta::new(collateral_vault_pda, false),
-            AccountMeta::new_readonly(loan_mint, false),
-            AccountMeta::new_readonly(collateral_mint, false),
-            AccountMeta::new(borrower_loan_account, false),
-            AccountMeta::new(borrower_collateral_account, false),
-            AccountMeta::new(fee_recipient.pubkey(), false),
-            AccountMeta::new(fee_recipient_loan_account, false),
-            AccountMeta::new_readonly(spl_token::id(), false),
-            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
-        ],
-        data: take_loan_data,
+    let take_loan_ix = |collateral: u64| {
+        let mut take_loan_data = Vec::new();
+        take_loan_data.extend_from_slice(&anchor_discriminator("global", "take_loan"));
+        take_loan_data.extend_from_slice(&loan_amount.to_le_bytes()); // borrow the full offer
+        take_loan_data.extend_from_slice(&collateral.to_le_bytes());
+
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(borrower.pubkey(), true),
+                AccountMeta::new_readonly(lending_market_pda, false),
+                AccountMeta::new(asset_pair_market_pda, false),
+                AccountMeta::new(orderbook_pda, false),
+                AccountMeta::new(lending_offer_pda, false),
+                AccountMeta::new(loan_pda, false),
+                AccountMeta::new(escrow_pda, false),
+                AccountMeta::new(collateral_vault_pda, false),
+                AccountMeta::new_readonly(loan_mint, false),
+                AccountMeta::new_readonly(collateral_mint, false),
+                AccountMeta::new_readonly(loan_price_feed, false),
+                AccountMeta::new_readonly(collateral_price_feed, false),
+                AccountMeta::new(borrower_loan_account, false),
+                AccountMeta::new(borrower_collateral_account, false),
+                AccountMeta::new(fee_recipient.pubkey(), false),
+                AccountMeta::new(fee_recipient_loan_account, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+            data: take_loan_data,
+        }
     };
 
+    // One base unit short of $1250 of collateral is rejected
     let tx = Transaction::new_signed_with_payer(
-        &[take_loan_ix],
+        &[take_loan_ix(collateral_amount - 1)],
+        Some(&borrower.pubkey()),
+        &[&borrower],
+        svm.latest_blockhash(),
+    );
+    assert!(
+        svm.send_transaction(tx).is_err(),
+        "Take loan below required collateral should fail"
+    );
+
+    let tx = Transaction::new_signed_with_payer(
+        &[take_loan_ix(collateral_amount)],
         Some(&borrower.pubkey()),
         &[&borrower],
         svm.latest_blockhash(),
     );
     svm.send_transaction(tx).expect("Take loan should succeed");
 
+    // Verify valuation recorded at origination ($1000 loan, $1250 collateral, 6 decimal quote units)
+    let loan: Loan = get_account(&svm, &loan_pda);
+    assert_eq!(loan.origination_loan_value, 1_000_000_000);
+    assert_eq!(loan.origination_collateral_value, 1_250_000_000);
+
     // Verify borrower fee (1%)
     let borrower_loan_balance_after = get_token_balance(&svm, &borrower_loan_account);
     let fee_recipient_balance_after = get_token_balance(&svm, &fee_recipient_loan_account);
@@ -590,6 +841,7 @@ fn test_full_lending_flow_with_fees() {
             AccountMeta::new(borrower.pubkey(), true),
             AccountMeta::new_readonly(lending_market_pda, false),
             AccountMeta::new(loan_pda, false),
+            AccountMeta::new(asset_pair_market_pda, false),
             AccountMeta::new(collateral_vault_pda, false),
             AccountMeta::new(borrower_loan_account, false),
             AccountMeta::new(borrower_collateral_account, false),
@@ -618,7 +870,7 @@ fn test_full_lending_flow_with_fees() {
     // Note: The actual interest calculation happens in the program based on time elapsed
     // For testing purposes, we're verifying the fee structure works correctly
     println!(" Loan repaid successfully");
-    println!("Collateral returned: {} SOL", collateral_amount / 1_000_000_000);
+    println!("Collateral returned: {} SOL", collateral_amount as f64 / 1_000_000_000.0);
 
     // Verify collateral was returned
     assert_eq!(
@@ -687,16 +939,23 @@ fn test_request_repayment_and_liquidation() {
 
     // Mint tokens
     let loan_amount = 1000_000000;
-    let collateral_amount = 1_250_000000000;
+    let collateral_amount = 12_500_000_000; // 12.5 SOL = $1250 at $100
 
     mint_tokens(&mut svm, &loan_mint, &lender_loan_account, &admin, loan_amount);
     mint_tokens(&mut svm, &collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
 
     // Create asset pair market
+    // Register mock oracle prices for both mints
+    let loan_price_feed = create_mock_price_feed(&mut svm, &admin, &loan_mint, USDC_PRICE, PRICE_EXPO);
+    let collateral_price_feed = create_mock_price_feed(&mut svm, &admin, &collateral_mint, SOL_PRICE, PRICE_EXPO);
+
     let (asset_pair_market_pda, _) = get_pda_asset_pair_market(&loan_mint, &collateral_mint);
+    let (orderbook_pda, _) = get_pda_orderbook(&asset_pair_market_pda);
 
     let mut create_pair_data = Vec::new();
     create_pair_data.extend_from_slice(&anchor_discriminator("global", "create_asset_pair_market"));
+    create_pair_data.extend_from_slice(&loan_mint.to_bytes()); // loan_feed_id: a mock feed is identified by its mint
+    create_pair_data.extend_from_slice(&collateral_mint.to_bytes()); // collateral_feed_id
 
     let create_pair_ix = Instruction {
         program_id,
@@ -704,8 +963,11 @@ fn test_request_repayment_and_liquidation() {
             AccountMeta::new(admin.pubkey(), true),
             AccountMeta::new_readonly(lending_market_pda, false),
             AccountMeta::new(asset_pair_market_pda, false),
+            AccountMeta::new(orderbook_pda, false),
             AccountMeta::new_readonly(loan_mint, false),
             AccountMeta::new_readonly(collateral_mint, false),
+            AccountMeta::new_readonly(loan_price_feed, false),
+            AccountMeta::new_readonly(collateral_price_feed, false),
             AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
         ],
         data: create_pair_data,
@@ -730,12 +992,20 @@ fn test_request_repayment_and_liquidation() {
     create_offer_data.extend_from_slice(&loan_amount.to_le_bytes());
     create_offer_data.extend_from_slice(&1000u64.to_le_bytes());
     create_offer_data.extend_from_slice(&8000u64.to_le_bytes());
+    create_offer_data.extend_from_slice(&0u64.to_le_bytes());
+    create_offer_data.push(0);
+    create_offer_data.extend_from_slice(&0u64.to_le_bytes());
+    create_offer_data.extend_from_slice(&(48i64 * 60 * 60).to_le_bytes());
+    create_offer_data.push(0);
+    create_offer_data.push(0);
 
     let create_offer_ix = Instruction {
         program_id,
         accounts: vec![
             AccountMeta::new(lender.pubkey(), true),
-            AccountMeta::new_readonly(asset_pair_market_pda, false),
+            AccountMeta::new_readonly(lending_market_pda, false),
+            AccountMeta::new(asset_pair_market_pda, false),
+            AccountMeta::new(orderbook_pda, false),
             AccountMeta::new(lending_offer_pda, false),
             AccountMeta::new(escrow_pda, false),
             AccountMeta::new_readonly(loan_mint, false),
@@ -760,6 +1030,7 @@ fn test_request_repayment_and_liquidation() {
 
     let mut take_loan_data = Vec::new();
     take_loan_data.extend_from_slice(&anchor_discriminator("global", "take_loan"));
+    take_loan_data.extend_from_slice(&loan_amount.to_le_bytes());
     take_loan_data.extend_from_slice(&collateral_amount.to_le_bytes());
 
     let take_loan_ix = Instruction {
@@ -767,13 +1038,16 @@ fn test_request_repayment_and_liquidation() {
         accounts: vec![
             AccountMeta::new(borrower.pubkey(), true),
             AccountMeta::new_readonly(lending_market_pda, false),
-            AccountMeta::new_readonly(asset_pair_market_pda, false),
+            AccountMeta::new(asset_pair_market_pda, false),
+            AccountMeta::new(orderbook_pda, false),
             AccountMeta::new(lending_offer_pda, false),
             AccountMeta::new(loan_pda, false),
             AccountMeta::new(escrow_pda, false),
             AccountMeta::new(collateral_vault_pda, false),
             AccountMeta::new_readonly(loan_mint, false),
             AccountMeta::new_readonly(collateral_mint, false),
+            AccountMeta::new_readonly(loan_price_feed, false),
+            AccountMeta::new_readonly(collateral_price_feed, false),
             AccountMeta::new(borrower_loan_account, false),
             AccountMeta::new(borrower_collateral_account, false),
             AccountMeta::new(fee_recipient.pubkey(), false),
@@ -803,6 +1077,7 @@ fn test_request_repayment_and_liquidation() {
         program_id,
         accounts: vec![
             AccountMeta::new_readonly(lender.pubkey(), true),
+            AccountMeta::new_readonly(lending_market_pda, false),
             AccountMeta::new(loan_pda, false),
         ],
         data: request_repayment_data,
@@ -817,28 +1092,48 @@ fn test_request_repayment_and_liquidation() {
     svm.send_transaction(tx).expect("Request repayment should succeed");
     println!(" Repayment requested - 48 hour deadline set");
 
-    // Simulate liquidation scenario (LTV > 120%)
-    println!("\n Simulating liquidation scenario...");
-    println!("Current LTV: 121% (above 120% threshold)");
-
     let lender_collateral_before = get_token_balance(&svm, &lender_collateral_account);
 
     let mut liquidate_loan_data = Vec::new();
     liquidate_loan_data.extend_from_slice(&anchor_discriminator("global", "liquidate_loan"));
-    liquidate_loan_data.extend_from_slice(&12100u64.to_le_bytes()); // 121% LTV
 
     let liquidate_loan_ix = Instruction {
         program_id,
         accounts: vec![
-            AccountMeta::new_readonly(lender.pubkey(), true),
+            AccountMeta::new(lender.pubkey(), true),
+            AccountMeta::new_readonly(lending_market_pda, false),
             AccountMeta::new(loan_pda, false),
+            AccountMeta::new(asset_pair_market_pda, false),
+            AccountMeta::new_readonly(loan_price_feed, false),
+            AccountMeta::new_readonly(collateral_price_feed, false),
             AccountMeta::new(collateral_vault_pda, false),
             AccountMeta::new(lender_collateral_account, false),
+            AccountMeta::new(borrower_collateral_account, false),
             AccountMeta::new_readonly(spl_token::id(), false),
         ],
         data: liquidate_loan_data,
     };
 
+    // Deadline not reached and LTV is healthy at oracle prices
+    let tx = Transaction::new_signed_with_payer(
+        std::slice::from_ref(&liquidate_loan_ix),
+        Some(&lender.pubkey()),
+        &[&lender],
+        svm.latest_blockhash(),
+    );
+    assert!(
+        svm.send_transaction(tx).is_err(),
+        "Liquidation of a healthy loan should fail"
+    );
+    println!(" Healthy loan cannot be liquidated");
+
+    // Simulate liquidation scenario: collateral price crash pushes LTV above 120%
+    // 12.5 SOL at $50 = $625 collateral for 1000 USDC debt => LTV 160%
+    println!("\n Simulating liquidation scenario...");
+    println!("SOL price drops from $100 to $50");
+    set_mock_price(&mut svm, &admin, &collateral_mint, 5_000_000_000);
+    svm.expire_blockhash();
+
     let tx = Transaction::new_signed_with_payer(
         &[liquidate_loan_ix],
         Some(&lender.pubkey()),
@@ -847,7 +1142,7 @@ fn test_request_repayment_and_liquidation() {
     );
     svm.send_transaction(tx).expect("Liquidation should succeed");
 
-    // Verify collateral was transferred to lender
+    // Collateral is worth less than the debt, so the lender seizes all of it
     let lender_collateral_after = get_token_balance(&svm, &lender_collateral_account);
     assert_eq!(
         lender_collateral_after - lender_collateral_before,
@@ -856,6 +1151,2160 @@ fn test_request_repayment_and_liquidation() {
     );
 
     println!(" Loan liquidated successfully");
-    println!("Collateral transferred to lender: {} SOL", collateral_amount / 1_000_000_000);
+    println!("Collateral transferred to lender: {} SOL", collateral_amount as f64 / 1_000_000_000.0);
     println!("No protocol fees on liquidation");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_deactivate_and_reactivate_asset_pair_market() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    svm.airdrop(&admin.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&lender.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&borrower.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+
+    let loan_amount = 1_000_000_000; // 1000 USDC
+    let collateral_amount = 12_500_000_000; // 12.5 SOL = $1250 for 80% LTV
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, 2 * loan_amount);
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
+
+    // Offer created while the pair is active
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms { offer_id: 1, loan_amount, ..Default::default() });
+    send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+
+    // Only the admin can pause the pair
+    let result = send_ix(&mut svm, asset_pair_admin_ix("deactivate_asset_pair_market", &lender.pubkey(), &pair), &lender, &[]);
+    assert!(result.is_err(), "Non-admin deactivation should fail");
+
+    send_ix(&mut svm, asset_pair_admin_ix("deactivate_asset_pair_market", &admin.pubkey(), &pair), &admin, &[])
+        .expect("Admin deactivation should succeed");
+    println!(" Asset pair market paused");
+
+    let result = send_ix(&mut svm, asset_pair_admin_ix("deactivate_asset_pair_market", &admin.pubkey(), &pair), &admin, &[]);
+    assert!(result.is_err(), "Deactivating a paused pair should fail");
+
+    // New offers and loans are blocked on a paused pair
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms { offer_id: 2, loan_amount, ..Default::default() });
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Offer on paused pair should fail");
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &lending_offer_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+    assert!(send_ix(&mut svm, ix, &borrower, &[]).is_err(), "Take loan on paused pair should fail");
+
+    // Unpausing restores borrowing
+    send_ix(&mut svm, asset_pair_admin_ix("reactivate_asset_pair_market", &admin.pubkey(), &pair), &admin, &[])
+        .expect("Admin reactivation should succeed");
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &lending_offer_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan on reactivated pair should succeed");
+    println!(" Asset pair market reactivated, loan taken");
+}
+
+#[test]
+fn test_emergency_pause() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let guardian = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    svm.airdrop(&admin.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&guardian.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&lender.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&borrower.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+
+    let loan_amount = 1_000_000_000; // 1000 USDC
+    let collateral_amount = 12_500_000_000; // 12.5 SOL = $1250 for 80% LTV
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, 3 * loan_amount);
+    mint_tokens(&mut svm, &pair.loan_mint, &borrower_loan_account, &admin, loan_amount);
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
+
+    // Register a separate guardian key
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "update_lending_market"));
+    data.extend_from_slice(&[0, 0, 0]); // fee_recipient, lender_fee_bps, borrower_fee_bps: None
+    data.push(1);
+    data.extend_from_slice(&guardian.pubkey().to_bytes());
+    data.extend_from_slice(&[0, 0]); // min_notice_duration, max_notice_duration: None
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(admin.pubkey(), true),
+            AccountMeta::new(pair.lending_market, false),
+        ],
+        data,
+    };
+    send_ix(&mut svm, ix, &admin, &[]).expect("Guardian update should succeed");
+
+    // Two offers: one is borrowed before the pause, one stays open
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms { offer_id: 1, loan_amount, ..Default::default() });
+    send_ix(&mut svm, ix, &lender, &[]).unwrap();
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms { offer_id: 2, loan_amount, ..Default::default() });
+    send_ix(&mut svm, ix, &lender, &[]).unwrap();
+    let (offer_1, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+    let (offer_2, _) = get_pda_lending_offer(&lender.pubkey(), 2);
+    let (loan_pda, _) = get_pda_loan(&offer_1, &borrower.pubkey());
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &offer_1,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+
+    // Only admin or guardian can trip the breaker
+    let result = send_ix(&mut svm, set_protocol_paused_ix(&lender.pubkey(), true), &lender, &[]);
+    assert!(result.is_err(), "Lender cannot pause the protocol");
+
+    send_ix(&mut svm, set_protocol_paused_ix(&guardian.pubkey(), true), &guardian, &[])
+        .expect("Guardian pause should succeed");
+    println!(" Protocol paused by guardian");
+
+    // New offers, loans and repayment calls are halted
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms { offer_id: 3, loan_amount, ..Default::default() });
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Offer creation should be paused");
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &offer_2,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+    assert!(send_ix(&mut svm, ix, &borrower, &[]).is_err(), "Take loan should be paused");
+
+    let ix = request_repayment_ix(&lender.pubkey(), &pair, &loan_pda);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Request repayment should be paused");
+
+    // Even an undercollateralized loan cannot be liquidated while paused
+    set_mock_price(&mut svm, &admin, &pair.collateral_mint, 5_000_000_000); // SOL $50 => LTV 160%
+    let lender_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &lender.pubkey());
+    let ix = liquidate_loan_ix(&lender.pubkey(), &pair, &loan_pda, &lender_collateral_account, &borrower_collateral_account);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Liquidation should be paused");
+
+    // Exits stay open: cancel the open offer and repay the loan
+    let lender_balance_before = get_token_balance(&svm, &lender_loan_account);
+    let ix = cancel_lending_offer_ix(&lender.pubkey(), &pair, &offer_2, &lender_loan_account);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Cancel should work while paused");
+    assert_eq!(get_token_balance(&svm, &lender_loan_account) - lender_balance_before, loan_amount);
+
+    let ix = repay_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &loan_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        &lender.pubkey(),
+        &lender_loan_account,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Repay should work while paused");
+    assert_eq!(get_token_balance(&svm, &borrower_collateral_account), collateral_amount);
+
+    // Guardian cannot lift the pause, only the admin can
+    let result = send_ix(&mut svm, set_protocol_paused_ix(&guardian.pubkey(), false), &guardian, &[]);
+    assert!(result.is_err(), "Guardian cannot unpause the protocol");
+
+    send_ix(&mut svm, set_protocol_paused_ix(&admin.pubkey(), false), &admin, &[])
+        .expect("Admin unpause should succeed");
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms { offer_id: 3, loan_amount, ..Default::default() });
+    send_ix(&mut svm, ix, &lender, &[]).expect("Offer creation should resume");
+    println!(" Protocol unpaused");
+}
+
+#[test]
+fn test_partial_fills_multiple_borrowers() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower_a = Keypair::new();
+    let borrower_b = Keypair::new();
+    let borrower_c = Keypair::new();
+
+    svm.airdrop(&admin.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&lender.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    for borrower in [&borrower_a, &borrower_b, &borrower_c] {
+        svm.airdrop(&borrower.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let loan_amount = 1_000_000_000; // 1000 USDC offered
+    let min_fill_amount = 100_000_000; // 100 USDC minimum per borrower
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, loan_amount);
+
+    let ix = create_lending_offer_ix(
+        &lender.pubkey(),
+        &pair,
+        &lender_loan_account,
+        &OfferTerms { loan_amount, min_fill_amount, ..Default::default() },
+    );
+    send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+
+    // Each borrower posts 12.5 SOL ($1250), enough for the whole offer at 80% LTV
+    let collateral_amount = 12_500_000_000;
+    let mut accounts = Vec::new();
+    for borrower in [&borrower_a, &borrower_b, &borrower_c] {
+        let loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+        let collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+        mint_tokens(&mut svm, &pair.collateral_mint, &collateral_account, &admin, collateral_amount);
+        accounts.push((loan_account, collateral_account));
+    }
+
+    let take = |svm: &mut LiteSVM, borrower: &Keypair, accounts: &(Pubkey, Pubkey), borrow_amount: u64| {
+        let ix = take_loan_ix(
+            &borrower.pubkey(),
+            &pair,
+            &lending_offer_pda,
+            &accounts.0,
+            &accounts.1,
+            borrow_amount,
+            collateral_amount,
+        );
+        send_ix(svm, ix, borrower, &[])
+    };
+
+    // Borrower A takes 400 USDC, offer stays active
+    take(&mut svm, &borrower_a, &accounts[0], 400_000_000).expect("First partial fill should succeed");
+    let offer: LendingOffer = get_account(&svm, &lending_offer_pda);
+    assert_eq!(offer.remaining_amount, 600_000_000);
+    assert!(offer.is_active);
+
+    // Fills below the minimum or above the remaining amount are rejected
+    assert!(take(&mut svm, &borrower_b, &accounts[1], 50_000_000).is_err(), "Fill below minimum should fail");
+    assert!(take(&mut svm, &borrower_b, &accounts[1], 700_000_000).is_err(), "Fill above remaining should fail");
+
+    // Borrower B takes 550 USDC, leaving 50 USDC below the minimum fill
+    take(&mut svm, &borrower_b, &accounts[1], 550_000_000).expect("Second partial fill should succeed");
+
+    // Borrower C can take the last 50 USDC since it is the whole remainder
+    take(&mut svm, &borrower_c, &accounts[2], 50_000_000).expect("Remainder fill should succeed");
+    let offer: LendingOffer = get_account(&svm, &lending_offer_pda);
+    assert_eq!(offer.remaining_amount, 0);
+    assert!(!offer.is_active, "Offer should be inactive once drained");
+
+    let (escrow_pda, _) = get_pda_escrow(&lending_offer_pda);
+    assert_eq!(get_token_balance(&svm, &escrow_pda), 0);
+
+    // One Loan per borrower, each with its own principal
+    for (borrower, principal) in [(&borrower_a, 400_000_000), (&borrower_b, 550_000_000), (&borrower_c, 50_000_000)] {
+        let (loan_pda, _) = get_pda_loan(&lending_offer_pda, &borrower.pubkey());
+        let loan: Loan = get_account(&svm, &loan_pda);
+        assert_eq!(loan.principal_amount, principal);
+    }
+
+    // Borrowers receive their fill minus the 1% borrower fee
+    assert_eq!(get_token_balance(&svm, &accounts[0].0), 396_000_000);
+    assert_eq!(get_token_balance(&svm, &accounts[1].0), 544_500_000);
+    assert_eq!(get_token_balance(&svm, &accounts[2].0), 49_500_000);
+
+    // The drained offer can still be cancelled to reclaim its rent
+    let ix = cancel_lending_offer_ix(&lender.pubkey(), &pair, &lending_offer_pda, &lender_loan_account);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Cancel of a drained offer should succeed");
+    assert!(svm.get_account(&lending_offer_pda).is_none_or(|account| account.data.is_empty()));
+    assert!(svm.get_account(&escrow_pda).is_none_or(|account| account.data.is_empty()));
+
+    println!(" Offer filled by three borrowers");
+}
+
+#[test]
+fn test_partial_repay() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    svm.airdrop(&admin.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&lender.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&borrower.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let loan_amount = 1_000_000_000; // 1000 USDC at 10% APR, 80% LTV
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, loan_amount);
+
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms::default());
+    send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+
+    let collateral_amount = 12_500_000_000; // 12.5 SOL = $1250
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &lending_offer_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+    let (loan_pda, _) = get_pda_loan(&lending_offer_pda, &borrower.pubkey());
+    let loan_start: Loan = get_account(&svm, &loan_pda);
+
+    // 73 days at 10% APR accrues 20 USDC of interest
+    warp_clock(&mut svm, 73 * 86400);
+    set_mock_price(&mut svm, &admin, &pair.loan_mint, USDC_PRICE);
+    set_mock_price(&mut svm, &admin, &pair.collateral_mint, SOL_PRICE);
+
+    let partial_repay = |svm: &mut LiteSVM, amount: u64, release_collateral: bool| {
+        let ix = partial_repay_ix(
+            &borrower.pubkey(),
+            &pair,
+            &loan_pda,
+            &borrower_loan_account,
+            &borrower_collateral_account,
+            &lender.pubkey(),
+            &lender_loan_account,
+            amount,
+            release_collateral,
+        );
+        send_ix(svm, ix, &borrower, &[])
+    };
+
+    // Repaying in another mint is rejected
+    let junk_mint = create_mint(&mut svm, &borrower.pubkey(), 6);
+    let borrower_junk_account = create_token_account(&mut svm, &junk_mint, &borrower.pubkey());
+    let lender_junk_account = create_token_account(&mut svm, &junk_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &junk_mint, &borrower_junk_account, &borrower, 1_000_000_000);
+    let ix = partial_repay_ix(
+        &borrower.pubkey(),
+        &pair,
+        &loan_pda,
+        &borrower_junk_account,
+        &borrower_collateral_account,
+        &lender.pubkey(),
+        &lender_junk_account,
+        500_000_000,
+        true,
+    );
+    assert!(send_ix(&mut svm, ix, &borrower, &[]).is_err(), "Partial repay in the wrong mint should fail");
+
+    // Paying the whole debt must go through repay_loan
+    assert!(partial_repay(&mut svm, 1_020_000_000, false).is_err(), "Partial repay of the full debt should fail");
+
+    // 220 USDC covers the 20 USDC of interest and 200 USDC of principal
+    partial_repay(&mut svm, 220_000_000, true).expect("Partial repay should succeed");
+
+    let loan: Loan = get_account(&svm, &loan_pda);
+    assert_eq!(loan.principal_amount, 800_000_000);
+    assert_eq!(loan.accrued_interest, 0);
+    assert_eq!(loan.last_interest_update, loan_start.last_interest_update + 73 * 86400);
+    assert!(loan.is_active);
+
+    // 20% of principal repaid releases 2.5 SOL, leaving exactly 80% LTV on the remaining 800 USDC
+    assert_eq!(loan.collateral_amount, 10_000_000_000);
+    assert_eq!(get_token_balance(&svm, &borrower_collateral_account), 2_500_000_000);
+
+    // Lender receives 220 USDC minus the 2% lender fee
+    assert_eq!(get_token_balance(&svm, &lender_loan_account), 215_600_000);
+    // Fee recipient holds the 1% borrower fee plus the 2% lender fee on the payment
+    assert_eq!(get_token_balance(&svm, &pair.fee_recipient_loan_account), 10_000_000 + 4_400_000);
+    assert_eq!(get_token_balance(&svm, &borrower_loan_account), 990_000_000 - 220_000_000);
+
+    // Closing the loan in another mint is rejected too
+    let ix = repay_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &loan_pda,
+        &borrower_junk_account,
+        &borrower_collateral_account,
+        &lender.pubkey(),
+        &lender_junk_account,
+    );
+    assert!(send_ix(&mut svm, ix, &borrower, &[]).is_err(), "Repay in the wrong mint should fail");
+
+    // The rest is repaid in full and closes the loan
+    mint_tokens(&mut svm, &pair.loan_mint, &borrower_loan_account, &admin, 30_000_000);
+    let ix = repay_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &loan_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        &lender.pubkey(),
+        &lender_loan_account,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Repaying the remaining debt should succeed");
+
+    assert_eq!(get_token_balance(&svm, &borrower_collateral_account), collateral_amount);
+
+    println!(" Loan partially repaid then closed");
+}
+
+#[test]
+fn test_deposit_and_withdraw_collateral() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    svm.airdrop(&admin.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&lender.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&borrower.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let loan_amount = 1_000_000_000; // 1000 USDC at 80% LTV
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, loan_amount);
+
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms::default());
+    send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+
+    // Borrower posts the minimum 12.5 SOL ($1250) and keeps 2.5 SOL aside
+    let collateral_amount = 12_500_000_000;
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, 15_000_000_000);
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &lending_offer_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+    let (loan_pda, _) = get_pda_loan(&lending_offer_pda, &borrower.pubkey());
+    let (collateral_vault_pda, _) = get_pda_collateral_vault(&loan_pda);
+
+    let withdraw = |svm: &mut LiteSVM, amount: u64| {
+        let ix = withdraw_collateral_ix(&borrower.pubkey(), &pair, &loan_pda, &borrower_collateral_account, amount);
+        send_ix(svm, ix, &borrower, &[])
+    };
+
+    // Loan sits exactly at 80% LTV, nothing can be withdrawn
+    assert!(withdraw(&mut svm, 1_000_000_000).is_err(), "Withdrawal above LTV should fail");
+
+    // Top up 2.5 SOL
+    let ix = deposit_collateral_ix(&borrower.pubkey(), &loan_pda, &borrower_collateral_account, 2_500_000_000);
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Deposit should succeed");
+
+    let loan: Loan = get_account(&svm, &loan_pda);
+    assert_eq!(loan.collateral_amount, 15_000_000_000);
+    assert_eq!(get_token_balance(&svm, &collateral_vault_pda), 15_000_000_000);
+    assert_eq!(get_token_balance(&svm, &borrower_collateral_account), 0);
+
+    // Surplus can come back out, but no further than 80% LTV
+    withdraw(&mut svm, 2_500_000_000).expect("Withdrawing the surplus should succeed");
+    assert!(withdraw(&mut svm, 1).is_err(), "Withdrawal past LTV should fail");
+
+    // SOL doubles to $200: 6.25 SOL ($1250) now covers the loan
+    set_mock_price(&mut svm, &admin, &pair.collateral_mint, 2 * SOL_PRICE);
+    withdraw(&mut svm, 6_250_000_000).expect("Withdrawal after price rise should succeed");
+
+    let loan: Loan = get_account(&svm, &loan_pda);
+    assert_eq!(loan.collateral_amount, 6_250_000_000);
+    assert_eq!(get_token_balance(&svm, &collateral_vault_pda), 6_250_000_000);
+    assert_eq!(get_token_balance(&svm, &borrower_collateral_account), 8_750_000_000);
+
+    println!(" Collateral deposited and withdrawn");
+}
+
+#[test]
+fn test_liquidation_returns_surplus_collateral() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    svm.airdrop(&admin.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&lender.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&borrower.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+    assert_eq!(market.liquidation_penalty_bps, 500);
+
+    // Only the admin can change the penalty, and only up to 20%
+    let ix = update_asset_pair_market_ix(&lender.pubkey(), &pair, &PairRiskParams { liquidation_penalty_bps: Some(1000), ..Default::default() });
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Non-admin update should fail");
+    let ix = update_asset_pair_market_ix(&admin.pubkey(), &pair, &PairRiskParams { liquidation_penalty_bps: Some(2001), ..Default::default() });
+    assert!(send_ix(&mut svm, ix, &admin, &[]).is_err(), "Penalty above 20% should fail");
+    let ix = update_asset_pair_market_ix(&admin.pubkey(), &pair, &PairRiskParams { liquidation_penalty_bps: Some(1000), ..Default::default() });
+    send_ix(&mut svm, ix, &admin, &[]).expect("Admin update should succeed");
+
+    let market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+    assert_eq!(market.liquidation_penalty_bps, 1000);
+
+    let loan_amount = 1_000_000_000; // 1000 USDC at 10% APR, 80% LTV
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    let lender_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, loan_amount);
+
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms::default());
+    send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+
+    let collateral_amount = 12_500_000_000; // 12.5 SOL = $1250
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &lending_offer_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+    let (loan_pda, _) = get_pda_loan(&lending_offer_pda, &borrower.pubkey());
+
+    // Lender calls the loan and the borrower lets the 48h notice lapse
+    let ix = request_repayment_ix(&lender.pubkey(), &pair, &loan_pda);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Request repayment should succeed");
+
+    warp_clock(&mut svm, 48 * 3600 + 1);
+    set_mock_price(&mut svm, &admin, &pair.loan_mint, USDC_PRICE);
+    set_mock_price(&mut svm, &admin, &pair.collateral_mint, SOL_PRICE);
+
+    let ix = liquidate_loan_ix(&lender.pubkey(), &pair, &loan_pda, &lender_collateral_account, &borrower_collateral_account);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Liquidation after deadline should succeed");
+
+    // Debt after 48h + 1s: 1000 USDC + 0.547949 USDC interest (per second, rounded up)
+    // Seized: debt + 10% penalty = $1100.602744 => 11.00602744 SOL at $100
+    let collateral_seized = 11_006_027_440;
+    assert_eq!(get_token_balance(&svm, &lender_collateral_account), collateral_seized);
+    assert_eq!(get_token_balance(&svm, &borrower_collateral_account), collateral_amount - collateral_seized);
+
+    println!(" Liquidation returned surplus collateral to the borrower");
+}
+
+#[test]
+fn test_keeper_liquidation() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+    let keeper = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower, &keeper] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    // Liquidate above 90% LTV with a 5% keeper bonus
+    let params = PairRiskParams { liquidation_ltv_bps: Some(9000), ..Default::default() };
+    send_ix(&mut svm, update_asset_pair_market_ix(&admin.pubkey(), &pair, &params), &admin, &[])
+        .expect("Admin update should succeed");
+    let market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+    assert_eq!(market.liquidation_ltv_bps, 9000);
+    assert_eq!(market.liquidation_bonus_bps, 500);
+
+    let loan_amount = 1_000_000_000; // 1000 USDC at 80% LTV
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, loan_amount);
+
+    // Offers at or above the liquidation threshold are rejected
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms { ltv_bps: 9000, ..Default::default() });
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Offer LTV at liquidation threshold should fail");
+
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms::default());
+    send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+
+    let collateral_amount = 12_500_000_000; // 12.5 SOL = $1250
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &lending_offer_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+
+    // Lowering the threshold to the offer's 80% LTV blocks new loans from it
+    let params = PairRiskParams { liquidation_ltv_bps: Some(8000), ..Default::default() };
+    send_ix(&mut svm, update_asset_pair_market_ix(&admin.pubkey(), &pair, &params), &admin, &[])
+        .expect("Admin update should succeed");
+    assert!(send_ix(&mut svm, ix.clone(), &borrower, &[]).is_err(), "Take loan at the liquidation threshold should fail");
+
+    let params = PairRiskParams { liquidation_ltv_bps: Some(9000), ..Default::default() };
+    send_ix(&mut svm, update_asset_pair_market_ix(&admin.pubkey(), &pair, &params), &admin, &[])
+        .expect("Admin update should succeed");
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+    let (loan_pda, _) = get_pda_loan(&lending_offer_pda, &borrower.pubkey());
+
+    let keeper_loan_account = create_token_account(&mut svm, &pair.loan_mint, &keeper.pubkey());
+    let keeper_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &keeper.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &keeper_loan_account, &admin, loan_amount);
+
+    let keeper_liquidate = |svm: &mut LiteSVM| {
+        let ix = keeper_liquidate_loan_ix(
+            &keeper.pubkey(),
+            &pair,
+            &loan_pda,
+            &keeper_loan_account,
+            &keeper_collateral_account,
+            &lender.pubkey(),
+            &lender_loan_account,
+            &borrower.pubkey(),
+            &borrower_collateral_account,
+        );
+        send_ix(svm, ix, &keeper, &[])
+    };
+
+    // Healthy at 80% LTV
+    assert!(keeper_liquidate(&mut svm).is_err(), "Keeper cannot liquidate a healthy loan");
+
+    // SOL drops to $85: $1062.5 collateral for 1000 USDC => LTV 94%
+    set_mock_price(&mut svm, &admin, &pair.collateral_mint, 8_500_000_000);
+    keeper_liquidate(&mut svm).expect("Keeper liquidation should succeed");
+
+    // Keeper paid the debt, lender receives it minus the 2% lender fee
+    assert_eq!(get_token_balance(&svm, &keeper_loan_account), 0);
+    assert_eq!(get_token_balance(&svm, &lender_loan_account), 980_000_000);
+    assert_eq!(get_token_balance(&svm, &pair.fee_recipient_loan_account), 10_000_000 + 20_000_000);
+
+    // Keeper gets $1050 of SOL at $85, the borrower keeps the rest
+    let collateral_to_keeper = 12_352_941_177;
+    assert_eq!(get_token_balance(&svm, &keeper_collateral_account), collateral_to_keeper);
+    assert_eq!(get_token_balance(&svm, &borrower_collateral_account), collateral_amount - collateral_to_keeper);
+
+    println!(" Keeper liquidated an undercollateralized loan");
+}
+
+#[test]
+fn test_collateral_auction() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower_a = Keypair::new();
+    let borrower_b = Keypair::new();
+    let bidder = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower_a, &borrower_b, &bidder] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let loan_amount = 1_000_000_000; // 1000 USDC split between two borrowers
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    let lender_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, loan_amount);
+
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms::default());
+    send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+
+    // Each borrower takes 500 USDC against 6.25 SOL ($625)
+    let collateral_amount = 6_250_000_000;
+    let mut accounts = Vec::new();
+    for borrower in [&borrower_a, &borrower_b] {
+        let loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+        let collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+        mint_tokens(&mut svm, &pair.collateral_mint, &collateral_account, &admin, collateral_amount);
+
+        let ix = take_loan_ix(
+            &borrower.pubkey(),
+            &pair,
+            &lending_offer_pda,
+            &loan_account,
+            &collateral_account,
+            500_000_000,
+            collateral_amount,
+        );
+        send_ix(&mut svm, ix, borrower, &[]).expect("Take loan should succeed");
+        accounts.push((loan_account, collateral_account));
+    }
+    let (loan_a, _) = get_pda_loan(&lending_offer_pda, &borrower_a.pubkey());
+    let (loan_b, _) = get_pda_loan(&lending_offer_pda, &borrower_b.pubkey());
+
+    // Healthy loans cannot be auctioned
+    let ix = start_collateral_auction_ix(&lender.pubkey(), &pair, &loan_a);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Auction of a healthy loan should fail");
+
+    // SOL drops to $50: 6.25 SOL = $312.5 for 500 USDC debt => LTV 160%
+    set_mock_price(&mut svm, &admin, &pair.collateral_mint, 5_000_000_000);
+    for loan in [&loan_a, &loan_b] {
+        let ix = start_collateral_auction_ix(&lender.pubkey(), &pair, loan);
+        send_ix(&mut svm, ix, &lender, &[]).expect("Auction start should succeed");
+    }
+
+    // Price runs from 110% to 80% of the $312.5 oracle value over an hour
+    let (auction_a, _) = get_pda_collateral_auction(&loan_a);
+    let auction: CollateralAuction = get_account(&svm, &auction_a);
+    assert_eq!(auction.debt, 500_000_000);
+    assert_eq!(auction.start_price, 343_750_000);
+    assert_eq!(auction.floor_price, 250_000_000);
+
+    // Loans under auction are frozen
+    let loan: Loan = get_account(&svm, &loan_a);
+    assert!(!loan.is_active);
+    mint_tokens(&mut svm, &pair.loan_mint, &accounts[0].0, &admin, 5_000_000); // enough to cover 500 USDC
+    let ix = repay_loan_ix(
+        &borrower_a.pubkey(),
+        &pair,
+        &loan_a,
+        &accounts[0].0,
+        &accounts[0].1,
+        &lender.pubkey(),
+        &lender_loan_account,
+    );
+    assert!(send_ix(&mut svm, ix, &borrower_a, &[]).is_err(), "Repay during auction should fail");
+
+    let bidder_loan_account = create_token_account(&mut svm, &pair.loan_mint, &bidder.pubkey());
+    let bidder_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &bidder.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &bidder_loan_account, &admin, 400_000_000);
+
+    let bid = |svm: &mut LiteSVM, max_price: u64| {
+        let ix = bid_collateral_auction_ix(
+            &bidder.pubkey(),
+            &pair,
+            &loan_a,
+            &bidder_loan_account,
+            &bidder_collateral_account,
+            &lender.pubkey(),
+            &lender_loan_account,
+            &borrower_a.pubkey(),
+            &accounts[0].0,
+            max_price,
+        );
+        send_ix(svm, ix, &bidder, &[])
+    };
+
+    // Half way through, the price has decayed to 296.875 USDC
+    let fee_recipient_balance_before = get_token_balance(&svm, &pair.fee_recipient_loan_account);
+    warp_clock(&mut svm, 30 * 60);
+    assert!(bid(&mut svm, 296_000_000).is_err(), "Bid below the current price should fail");
+    bid(&mut svm, 296_875_000).expect("Bid at the current price should succeed");
+
+    // Proceeds are below the debt, so all of them go to the lender minus the 2% lender fee
+    assert_eq!(get_token_balance(&svm, &lender_loan_account), 296_875_000 - 5_937_500);
+    assert_eq!(get_token_balance(&svm, &bidder_loan_account), 400_000_000 - 296_875_000);
+    assert_eq!(get_token_balance(&svm, &bidder_collateral_account), collateral_amount);
+    assert_eq!(get_token_balance(&svm, &accounts[0].0), 500_000_000);
+
+    assert_eq!(get_token_balance(&svm, &pair.fee_recipient_loan_account), fee_recipient_balance_before + 5_937_500);
+
+    // Loan B gets no bid: the lender can only settle once the floor is reached
+    let ix = settle_collateral_auction_ix(&lender.pubkey(), &pair, &loan_b, &lender_collateral_account);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Settle before the floor should fail");
+
+    warp_clock(&mut svm, 30 * 60);
+    let ix = settle_collateral_auction_ix(&lender.pubkey(), &pair, &loan_b, &lender_collateral_account);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Settle at the floor should succeed");
+    assert_eq!(get_token_balance(&svm, &lender_collateral_account), collateral_amount);
+
+    println!(" Collateral auctions filled and settled");
+}
+
+#[test]
+fn test_per_second_interest() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let loan_amount = 1_000_000_000; // 1000 USDC at 10% APR
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, loan_amount);
+
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms::default());
+    send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+
+    let collateral_amount = 12_500_000_000;
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
+    mint_tokens(&mut svm, &pair.loan_mint, &borrower_loan_account, &admin, 20_000_000); // covers fee and interest
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &lending_offer_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+    let (loan_pda, _) = get_pda_loan(&lending_offer_pda, &borrower.pubkey());
+
+    // One second short of a day still accrues interest: ceil(1000 USDC * 10% * 86399 / 31536000)
+    warp_clock(&mut svm, 86399);
+    let ix = repay_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &loan_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        &lender.pubkey(),
+        &lender_loan_account,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Repay loan should succeed");
+
+    let debt = 1_000_273_970;
+    let lender_fee = debt * 200 / 10000;
+    assert_eq!(get_token_balance(&svm, &lender_loan_account), debt - lender_fee);
+    assert_eq!(get_token_balance(&svm, &borrower_loan_account), 990_000_000 + 20_000_000 - debt);
+
+    println!(" Interest accrued per second");
+}
+
+#[test]
+fn test_compounding_interest_modes() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower_daily = Keypair::new();
+    let borrower_continuous = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower_daily, &borrower_continuous] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    // Two 1000 USDC offers at 10% APR, one per compounding mode
+    let loan_amount = 1_000_000_000;
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, 2 * loan_amount);
+
+    let modes = [
+        (1, InterestMode::CompoundDaily, &borrower_daily),
+        (2, InterestMode::CompoundContinuous, &borrower_continuous),
+    ];
+
+    let collateral_amount = 12_500_000_000;
+    let mut loans = Vec::new();
+    for (offer_id, interest_mode, borrower) in modes {
+        let ix = create_lending_offer_ix(
+            &lender.pubkey(),
+            &pair,
+            &lender_loan_account,
+            &OfferTerms { offer_id, interest_mode, ..Default::default() },
+        );
+        send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+        let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), offer_id);
+
+        let offer: LendingOffer = get_account(&svm, &lending_offer_pda);
+        assert_eq!(offer.interest_mode, interest_mode);
+
+        let loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+        let collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+        mint_tokens(&mut svm, &pair.collateral_mint, &collateral_account, &admin, collateral_amount);
+        mint_tokens(&mut svm, &pair.loan_mint, &loan_account, &admin, 200_000_000); // covers fee and interest
+
+        let ix = take_loan_ix(
+            &borrower.pubkey(),
+            &pair,
+            &lending_offer_pda,
+            &loan_account,
+            &collateral_account,
+            loan_amount,
+            collateral_amount,
+        );
+        send_ix(&mut svm, ix, borrower, &[]).expect("Take loan should succeed");
+
+        let (loan_pda, _) = get_pda_loan(&lending_offer_pda, &borrower.pubkey());
+        let loan: Loan = get_account(&svm, &loan_pda);
+        assert_eq!(loan.interest_mode, interest_mode);
+        loans.push((borrower, loan_pda, loan_account, collateral_account));
+    }
+
+    // One year later: (1 + 0.1/365)^365 - 1 = 10.5155782% daily, e^0.1 - 1 = 10.5170918% continuous
+    warp_clock(&mut svm, 365 * 86400);
+    let now = svm.get_sysvar::<solana_sdk::clock::Clock>().unix_timestamp;
+
+    for ((borrower, loan_pda, loan_account, collateral_account), expected_interest) in
+        loans.iter().zip([105_155_782u64, 105_170_919])
+    {
+        let loan: Loan = get_account(&svm, loan_pda);
+        let asset_pair_market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+        let interest = loan.calculate_interest(now, &asset_pair_market).unwrap();
+        assert!(interest.abs_diff(expected_interest) <= 1, "interest {interest} expected {expected_interest}");
+
+        let lender_balance_before = get_token_balance(&svm, &lender_loan_account);
+        let ix = repay_loan_ix(
+            &borrower.pubkey(),
+            &pair,
+            loan_pda,
+            loan_account,
+            collateral_account,
+            &lender.pubkey(),
+            &lender_loan_account,
+        );
+        send_ix(&mut svm, ix, borrower, &[]).expect("Repay loan should succeed");
+
+        let debt = loan_amount + interest;
+        let lender_fee = debt * 200 / 10000;
+        assert_eq!(get_token_balance(&svm, &lender_loan_account) - lender_balance_before, debt - lender_fee);
+    }
+
+    println!(" Daily and continuous compounding loans repaid");
+}
+
+#[test]
+fn test_variable_rate_interest() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower_1 = Keypair::new();
+    let borrower_2 = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower_1, &borrower_2] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let loan_amount = 1_000_000_000;
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, loan_amount);
+
+    let variable_terms = OfferTerms { interest_rate_bps: 0, interest_mode: InterestMode::Variable, ..Default::default() };
+
+    // Variable offers need a rate model on the pair
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &variable_terms);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Variable offer without a rate model should fail");
+
+    // Kink must be strictly between 0% and 100%
+    let bad_model = RateModel { base_rate_bps: 200, slope_bps: 1000, jump_slope_bps: 5000, kink_bps: 10000 };
+    let ix = update_asset_pair_market_ix(&admin.pubkey(), &pair, &PairRiskParams { rate_model: Some(bad_model), ..Default::default() });
+    assert!(send_ix(&mut svm, ix, &admin, &[]).is_err(), "Invalid rate model should fail");
+
+    // 2% base, +10% up to 80% utilization, +50% from 80% to 100%
+    let rate_model = RateModel { base_rate_bps: 200, slope_bps: 1000, jump_slope_bps: 5000, kink_bps: 8000 };
+    let ix = update_asset_pair_market_ix(&admin.pubkey(), &pair, &PairRiskParams { rate_model: Some(rate_model), ..Default::default() });
+    send_ix(&mut svm, ix, &admin, &[]).expect("Setting the rate model should succeed");
+
+    // A fixed rate on a variable offer is rejected
+    let ix = create_lending_offer_ix(
+        &lender.pubkey(),
+        &pair,
+        &lender_loan_account,
+        &OfferTerms { interest_mode: InterestMode::Variable, ..Default::default() },
+    );
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Variable offer with a fixed rate should fail");
+
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &variable_terms);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Variable offer should succeed");
+    let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), variable_terms.offer_id);
+
+    let asset_pair_market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+    assert_eq!(asset_pair_market.rate_model, Some(rate_model));
+    assert_eq!(asset_pair_market.total_available, loan_amount);
+    assert_eq!(asset_pair_market.variable_rate_bps(), 200);
+
+    let take_half = |svm: &mut LiteSVM, borrower: &Keypair| {
+        let loan_account = create_token_account(svm, &pair.loan_mint, &borrower.pubkey());
+        let collateral_account = create_token_account(svm, &pair.collateral_mint, &borrower.pubkey());
+        mint_tokens(svm, &pair.collateral_mint, &collateral_account, &admin, 6_250_000_000);
+        mint_tokens(svm, &pair.loan_mint, &loan_account, &admin, 500_000_000); // covers fee and interest
+
+        let ix = take_loan_ix(
+            &borrower.pubkey(),
+            &pair,
+            &lending_offer_pda,
+            &loan_account,
+            &collateral_account,
+            loan_amount / 2,
+            6_250_000_000,
+        );
+        send_ix(svm, ix, borrower, &[]).expect("Take loan should succeed");
+        let (loan_pda, _) = get_pda_loan(&lending_offer_pda, &borrower.pubkey());
+        (loan_pda, loan_account, collateral_account)
+    };
+
+    // 50% utilization: 2% + 10% * 50/80 = 8.25%
+    let (loan_1, loan_account_1, collateral_account_1) = take_half(&mut svm, &borrower_1);
+    let asset_pair_market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+    assert_eq!(asset_pair_market.total_available, loan_amount / 2);
+    assert_eq!(asset_pair_market.total_borrowed, loan_amount / 2);
+    assert_eq!(asset_pair_market.utilization_bps(), 5000);
+    assert_eq!(asset_pair_market.variable_rate_bps(), 825);
+
+    warp_clock(&mut svm, 365 * 86400);
+    set_mock_price(&mut svm, &admin, &pair.loan_mint, USDC_PRICE);
+    set_mock_price(&mut svm, &admin, &pair.collateral_mint, SOL_PRICE);
+
+    let now = svm.get_sysvar::<solana_sdk::clock::Clock>().unix_timestamp;
+    let loan: Loan = get_account(&svm, &loan_1);
+    assert_eq!(loan.calculate_interest(now, &asset_pair_market).unwrap(), 41_250_000);
+
+    // 100% utilization: 2% + 10% + 50% = 62%, the first loan's rate moves with it
+    let (loan_2, _, _) = take_half(&mut svm, &borrower_2);
+    let asset_pair_market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+    assert_eq!(asset_pair_market.utilization_bps(), 10000);
+    assert_eq!(asset_pair_market.variable_rate_bps(), 6200);
+
+    warp_clock(&mut svm, 365 * 86400);
+    let now = svm.get_sysvar::<solana_sdk::clock::Clock>().unix_timestamp;
+
+    let loan: Loan = get_account(&svm, &loan_2);
+    assert_eq!(loan.calculate_interest(now, &asset_pair_market).unwrap(), 310_000_000);
+
+    let loan: Loan = get_account(&svm, &loan_1);
+    let interest = loan.calculate_interest(now, &asset_pair_market).unwrap();
+    assert_eq!(interest, 41_250_000 + 310_000_000);
+
+    let lender_balance_before = get_token_balance(&svm, &lender_loan_account);
+    let ix = repay_loan_ix(
+        &borrower_1.pubkey(),
+        &pair,
+        &loan_1,
+        &loan_account_1,
+        &collateral_account_1,
+        &lender.pubkey(),
+        &lender_loan_account,
+    );
+    send_ix(&mut svm, ix, &borrower_1, &[]).expect("Repay loan should succeed");
+
+    let debt = loan_amount / 2 + interest;
+    let lender_fee = debt * 200 / 10000;
+    assert_eq!(get_token_balance(&svm, &lender_loan_account) - lender_balance_before, debt - lender_fee);
+
+    // Repaid principal leaves the pair's utilization
+    let asset_pair_market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+    assert_eq!(asset_pair_market.total_borrowed, loan_amount / 2);
+    assert_eq!(asset_pair_market.total_available, 0);
+
+    println!(" Variable rate follows utilization");
+}
+
+#[test]
+fn test_penalty_rate_after_deadline() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let loan_amount = 1_000_000_000;
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, loan_amount);
+
+    // Penalty above 100% APR is rejected
+    let ix = create_lending_offer_ix(
+        &lender.pubkey(),
+        &pair,
+        &lender_loan_account,
+        &OfferTerms { penalty_rate_bps: 10001, ..Default::default() },
+    );
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Penalty rate above 100% should fail");
+
+    // 1000 USDC at 10% APR, plus 36.5% APR (1 USDC a day) once the deadline passes
+    let ix = create_lending_offer_ix(
+        &lender.pubkey(),
+        &pair,
+        &lender_loan_account,
+        &OfferTerms { penalty_rate_bps: 3650, ..Default::default() },
+    );
+    send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+
+    let offer: LendingOffer = get_account(&svm, &lending_offer_pda);
+    assert_eq!(offer.penalty_rate_bps, 3650);
+
+    let collateral_amount = 12_500_000_000;
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
+    mint_tokens(&mut svm, &pair.loan_mint, &borrower_loan_account, &admin, 30_000_000); // covers fee and interest
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &lending_offer_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+    let (loan_pda, _) = get_pda_loan(&lending_offer_pda, &borrower.pubkey());
+
+    let ix = request_repayment_ix(&lender.pubkey(), &pair, &loan_pda);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Request repayment should succeed");
+
+    let loan: Loan = get_account(&svm, &loan_pda);
+    assert_eq!(loan.penalty_rate_bps, 3650);
+    let asset_pair_market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+
+    // Inside the notice period only the normal rate applies: ceil(1000 USDC * 10% * 2 days / 365)
+    let deadline = loan.repayment_deadline.unwrap();
+    assert_eq!(loan.calculate_interest(deadline, &asset_pair_market).unwrap(), 547_946);
+
+    // 10 days past the deadline: ceil(1000 USDC * 10% * 12 days / 365) + 1000 USDC * 36.5% * 10 days / 365
+    warp_clock(&mut svm, 12 * 86400);
+    let now = svm.get_sysvar::<solana_sdk::clock::Clock>().unix_timestamp;
+    let debt = loan.calculate_repayment_amount(now, &asset_pair_market).unwrap();
+    assert_eq!(debt, loan_amount + 3_287_672 + 10_000_000);
+
+    let ix = repay_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &loan_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        &lender.pubkey(),
+        &lender_loan_account,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Repay loan should succeed");
+
+    let lender_fee = debt * 200 / 10000;
+    assert_eq!(get_token_balance(&svm, &lender_loan_account), debt - lender_fee);
+
+    println!(" Penalty rate charged past the deadline");
+}
+
+#[test]
+fn test_cancel_and_extend_repayment_request() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let loan_amount = 1_000_000_000;
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    let lender_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, loan_amount);
+
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &OfferTerms::default());
+    send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+
+    let collateral_amount = 12_500_000_000;
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &lending_offer_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+    let (loan_pda, _) = get_pda_loan(&lending_offer_pda, &borrower.pubkey());
+
+    // Nothing to cancel or extend before a request
+    let ix = cancel_repayment_request_ix(&lender.pubkey(), &pair, &loan_pda);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Cancel without a request should fail");
+
+    let ix = request_repayment_ix(&lender.pubkey(), &pair, &loan_pda);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Request repayment should succeed");
+    let deadline = get_account::<Loan>(&svm, &loan_pda).repayment_deadline.unwrap();
+
+    // A second request would reset the notice period
+    warp_clock(&mut svm, 3600);
+    let ix = request_repayment_ix(&lender.pubkey(), &pair, &loan_pda);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Re-requesting repayment should fail");
+
+    // Only the lender can extend, and only to a later deadline
+    let ix = extend_repayment_deadline_ix(&borrower.pubkey(), &pair, &loan_pda, deadline + 86400);
+    assert!(send_ix(&mut svm, ix, &borrower, &[]).is_err(), "Only the lender can extend");
+
+    let ix = extend_repayment_deadline_ix(&lender.pubkey(), &pair, &loan_pda, deadline - 1);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Shortening the deadline should fail");
+
+    let ix = extend_repayment_deadline_ix(&lender.pubkey(), &pair, &loan_pda, deadline + 86400);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Extend repayment deadline should succeed");
+    assert_eq!(get_account::<Loan>(&svm, &loan_pda).repayment_deadline, Some(deadline + 86400));
+
+    // The original deadline has passed but the extended one has not
+    warp_clock(&mut svm, 48 * 3600);
+    set_mock_price(&mut svm, &admin, &pair.loan_mint, USDC_PRICE);
+    set_mock_price(&mut svm, &admin, &pair.collateral_mint, SOL_PRICE);
+    let ix = liquidate_loan_ix(&lender.pubkey(), &pair, &loan_pda, &lender_collateral_account, &borrower_collateral_account);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Liquidation before the extended deadline should fail");
+
+    // Cancelling clears the deadline, and the lender can call the loan again afterwards
+    let ix = cancel_repayment_request_ix(&lender.pubkey(), &pair, &loan_pda);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Cancel repayment request should succeed");
+    assert_eq!(get_account::<Loan>(&svm, &loan_pda).repayment_deadline, None);
+
+    warp_clock(&mut svm, 86400);
+    set_mock_price(&mut svm, &admin, &pair.loan_mint, USDC_PRICE);
+    set_mock_price(&mut svm, &admin, &pair.collateral_mint, SOL_PRICE);
+    let ix = liquidate_loan_ix(&lender.pubkey(), &pair, &loan_pda, &lender_collateral_account, &borrower_collateral_account);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Liquidation after cancelling should fail");
+
+    let ix = request_repayment_ix(&lender.pubkey(), &pair, &loan_pda);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Request repayment after cancelling should succeed");
+
+    println!(" Repayment requests cancelled and extended");
+}
+
+#[test]
+fn test_offer_notice_period() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let market: LendingMarket = get_account(&svm, &pair.lending_market);
+    assert_eq!(market.min_notice_duration, LendingMarket::DEFAULT_MIN_NOTICE_DURATION);
+    assert_eq!(market.max_notice_duration, LendingMarket::DEFAULT_MAX_NOTICE_DURATION);
+
+    let notice_bounds_ix = |min_notice_duration: i64, max_notice_duration: i64| {
+        let mut data = Vec::new();
+        data.extend_from_slice(&anchor_discriminator("global", "update_lending_market"));
+        data.extend_from_slice(&[0, 0, 0, 0]); // fee_recipient, lender_fee_bps, borrower_fee_bps, guardian: None
+        data.push(1);
+        data.extend_from_slice(&min_notice_duration.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(&max_notice_duration.to_le_bytes());
+
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(admin.pubkey(), true),
+                AccountMeta::new(pair.lending_market, false),
+            ],
+            data,
+        }
+    };
+
+    // Minimum above maximum is rejected
+    let result = send_ix(&mut svm, notice_bounds_ix(7 * 86400, 3600), &admin, &[]);
+    assert!(result.is_err(), "Minimum notice above maximum should fail");
+
+    // Notice between 1 hour and 7 days
+    send_ix(&mut svm, notice_bounds_ix(3600, 7 * 86400), &admin, &[]).expect("Notice bounds update should succeed");
+
+    let loan_amount = 1_000_000_000;
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, loan_amount);
+
+    for (offer_id, notice_duration) in [(1, 1800), (2, 8 * 86400)] {
+        let ix = create_lending_offer_ix(
+            &lender.pubkey(),
+            &pair,
+            &lender_loan_account,
+            &OfferTerms { offer_id, notice_duration, ..Default::default() },
+        );
+        assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Notice outside the bounds should fail");
+    }
+
+    // 6 hour notice
+    let notice_duration = 6 * 3600;
+    let ix = create_lending_offer_ix(
+        &lender.pubkey(),
+        &pair,
+        &lender_loan_account,
+        &OfferTerms { notice_duration, ..Default::default() },
+    );
+    send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+    assert_eq!(get_account::<LendingOffer>(&svm, &lending_offer_pda).notice_duration, notice_duration);
+
+    let collateral_amount = 12_500_000_000;
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &lending_offer_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+    let (loan_pda, _) = get_pda_loan(&lending_offer_pda, &borrower.pubkey());
+    assert_eq!(get_account::<Loan>(&svm, &loan_pda).notice_duration, notice_duration);
+
+    // Later bound changes don't touch the loan's terms
+    send_ix(&mut svm, notice_bounds_ix(12 * 3600, 7 * 86400), &admin, &[]).expect("Notice bounds update should succeed");
+
+    let ix = request_repayment_ix(&lender.pubkey(), &pair, &loan_pda);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Request repayment should succeed");
+
+    let now = svm.get_sysvar::<solana_sdk::clock::Clock>().unix_timestamp;
+    let loan: Loan = get_account(&svm, &loan_pda);
+    assert_eq!(loan.repayment_deadline, Some(now + notice_duration));
+
+    println!(" Notice period set per offer");
+}
+
+#[test]
+fn test_fixed_term_loan() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let loan_amount = 1_000_000_000;
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    let lender_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, loan_amount);
+
+    let ix = create_lending_offer_ix(
+        &lender.pubkey(),
+        &pair,
+        &lender_loan_account,
+        &OfferTerms { duration: Some(0), ..Default::default() },
+    );
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Zero duration should fail");
+
+    let ix = create_lending_offer_ix(
+        &lender.pubkey(),
+        &pair,
+        &lender_loan_account,
+        &OfferTerms { duration: Some(i64::MAX), ..Default::default() },
+    );
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Duration above the 10 year limit should fail");
+
+    // 30 day term
+    let duration = 30 * 86400;
+    let ix = create_lending_offer_ix(
+        &lender.pubkey(),
+        &pair,
+        &lender_loan_account,
+        &OfferTerms { duration: Some(duration), ..Default::default() },
+    );
+    send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    let (lending_offer_pda, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+    assert_eq!(get_account::<LendingOffer>(&svm, &lending_offer_pda).duration, Some(duration));
+
+    let collateral_amount = 12_500_000_000;
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &lending_offer_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+    let (loan_pda, _) = get_pda_loan(&lending_offer_pda, &borrower.pubkey());
+
+    let now = svm.get_sysvar::<solana_sdk::clock::Clock>().unix_timestamp;
+    let loan: Loan = get_account(&svm, &loan_pda);
+    assert_eq!(loan.maturity, Some(now + duration));
+    assert_eq!(loan.due_time(), Some(now + duration + Loan::MATURITY_GRACE_PERIOD));
+
+    // No early call
+    let ix = request_repayment_ix(&lender.pubkey(), &pair, &loan_pda);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Calling a fixed-term loan before maturity should fail");
+
+    // Matured but still inside the grace period
+    warp_clock(&mut svm, duration);
+    set_mock_price(&mut svm, &admin, &pair.loan_mint, USDC_PRICE);
+    set_mock_price(&mut svm, &admin, &pair.collateral_mint, SOL_PRICE);
+    let ix = liquidate_loan_ix(&lender.pubkey(), &pair, &loan_pda, &lender_collateral_account, &borrower_collateral_account);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Liquidation inside the grace period should fail");
+
+    // Grace period over: the lender liquidates without request_repayment
+    warp_clock(&mut svm, Loan::MATURITY_GRACE_PERIOD + 1);
+    set_mock_price(&mut svm, &admin, &pair.loan_mint, USDC_PRICE);
+    set_mock_price(&mut svm, &admin, &pair.collateral_mint, SOL_PRICE);
+    let ix = liquidate_loan_ix(&lender.pubkey(), &pair, &loan_pda, &lender_collateral_account, &borrower_collateral_account);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Liquidation after the grace period should succeed");
+
+    // Healthy collateral: the lender takes the debt plus penalty, the rest goes back to the borrower
+    assert!(get_token_balance(&svm, &lender_collateral_account) > 0);
+    assert!(get_token_balance(&svm, &borrower_collateral_account) > 0);
+
+    println!(" Fixed-term loan liquidated after maturity");
+}
+
+#[test]
+fn test_refinance_loan() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let old_lender = Keypair::new();
+    let new_lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    for signer in [&admin, &old_lender, &new_lender, &borrower] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    // Old offer: 1000 USDC at 10% APR
+    let loan_amount = 1_000_000_000;
+    let old_lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &old_lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &old_lender_loan_account, &admin, loan_amount);
+    let ix = create_lending_offer_ix(&old_lender.pubkey(), &pair, &old_lender_loan_account, &OfferTerms::default());
+    send_ix(&mut svm, ix, &old_lender, &[]).expect("Lending offer creation should succeed");
+    let (old_offer, _) = get_pda_lending_offer(&old_lender.pubkey(), 1);
+
+    // New offers: 2000 USDC at 5% APR and 80% LTV, and one at 50% LTV the collateral can't cover
+    let new_lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &new_lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &new_lender_loan_account, &admin, 4 * loan_amount);
+    for (offer_id, ltv_bps) in [(1, 8000), (2, 5000)] {
+        let terms = OfferTerms { offer_id, loan_amount: 2 * loan_amount, interest_rate_bps: 500, ltv_bps, ..Default::default() };
+        let ix = create_lending_offer_ix(&new_lender.pubkey(), &pair, &new_lender_loan_account, &terms);
+        send_ix(&mut svm, ix, &new_lender, &[]).expect("Lending offer creation should succeed");
+    }
+    let (new_offer, _) = get_pda_lending_offer(&new_lender.pubkey(), 1);
+    let (low_ltv_offer, _) = get_pda_lending_offer(&new_lender.pubkey(), 2);
+
+    let collateral_amount = 12_500_000_000;
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &old_offer,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        collateral_amount,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+    let (old_loan, _) = get_pda_loan(&old_offer, &borrower.pubkey());
+
+    // 73 days at 10% APR: 20 USDC of interest, 1020 USDC to refinance
+    warp_clock(&mut svm, 73 * 86400);
+    set_mock_price(&mut svm, &admin, &pair.loan_mint, USDC_PRICE);
+    set_mock_price(&mut svm, &admin, &pair.collateral_mint, SOL_PRICE);
+
+    let refinance = |svm: &mut LiteSVM, lending_offer: &Pubkey| {
+        let ix = refinance_loan_ix(
+            &borrower.pubkey(),
+            &pair,
+            &old_loan,
+            &old_lender.pubkey(),
+            &old_lender_loan_account,
+            lending_offer,
+            &borrower_loan_account,
+        );
+        send_ix(svm, ix, &borrower, &[])
+    };
+
+    assert!(refinance(&mut svm, &low_ltv_offer).is_err(), "Refinancing beyond the new offer's LTV should fail");
+
+    let borrower_balance_before = get_token_balance(&svm, &borrower_loan_account);
+    let fee_recipient_balance_before = get_token_balance(&svm, &pair.fee_recipient_loan_account);
+    refinance(&mut svm, &new_offer).expect("Refinance should succeed");
+
+    let debt = 1_020_000_000;
+    let lender_fee = debt * 200 / 10000;
+    let borrower_fee = debt * 100 / 10000;
+
+    // Old lender repaid in full, minus the lender fee
+    assert_eq!(get_token_balance(&svm, &old_lender_loan_account), debt - lender_fee);
+    assert_eq!(get_token_balance(&svm, &pair.fee_recipient_loan_account) - fee_recipient_balance_before, lender_fee + borrower_fee);
+    assert_eq!(borrower_balance_before - get_token_balance(&svm, &borrower_loan_account), borrower_fee);
+
+    // Old loan and vault are gone, the new loan holds the debt and all the collateral
+    let (old_collateral_vault, _) = get_pda_collateral_vault(&old_loan);
+    assert!(svm.get_account(&old_loan).is_none_or(|account| account.data.is_empty()));
+    assert!(svm.get_account(&old_collateral_vault).is_none_or(|account| account.data.is_empty()));
+
+    let (new_loan_pda, _) = get_pda_loan(&new_offer, &borrower.pubkey());
+    let new_loan: Loan = get_account(&svm, &new_loan_pda);
+    assert_eq!(new_loan.lender.to_bytes(), new_lender.pubkey().to_bytes());
+    assert_eq!(new_loan.principal_amount, debt);
+    assert_eq!(new_loan.interest_rate_bps, 500);
+    assert_eq!(new_loan.collateral_amount, collateral_amount);
+    let (new_collateral_vault, _) = get_pda_collateral_vault(&new_loan_pda);
+    assert_eq!(get_token_balance(&svm, &new_collateral_vault), collateral_amount);
+
+    let offer: LendingOffer = get_account(&svm, &new_offer);
+    assert_eq!(offer.remaining_amount, 2 * loan_amount - debt);
+
+    let asset_pair_market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+    assert_eq!(asset_pair_market.total_borrowed, debt);
+    assert_eq!(asset_pair_market.total_available, 4 * loan_amount - debt);
+
+    println!(" Loan refinanced into a cheaper offer");
+}
+
+#[test]
+fn test_borrow_request() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let terms = RequestTerms::default();
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, terms.loan_amount);
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, terms.collateral_amount);
+
+    // A cancelled request returns its collateral
+    let ix = create_borrow_request_ix(&borrower.pubkey(), &pair, &borrower_collateral_account, &terms);
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Borrow request creation should succeed");
+    let (cancelled_request, _) = get_pda_borrow_request(&borrower.pubkey(), 1);
+    assert_eq!(get_token_balance(&svm, &borrower_collateral_account), 0);
+
+    let ix = cancel_borrow_request_ix(&borrower.pubkey(), &cancelled_request, &borrower_collateral_account);
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Cancel borrow request should succeed");
+    assert_eq!(get_token_balance(&svm, &borrower_collateral_account), terms.collateral_amount);
+
+    let terms = RequestTerms { request_id: 2, ..Default::default() };
+    let ix = create_borrow_request_ix(&borrower.pubkey(), &pair, &borrower_collateral_account, &terms);
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Borrow request creation should succeed");
+    let (borrow_request, _) = get_pda_borrow_request(&borrower.pubkey(), 2);
+
+    let fill = |svm: &mut LiteSVM, interest_rate_bps: u64| {
+        let ix = fill_borrow_request_ix(
+            &lender.pubkey(),
+            &pair,
+            &borrow_request,
+            &borrower.pubkey(),
+            &lender_loan_account,
+            &borrower_loan_account,
+            interest_rate_bps,
+        );
+        send_ix(svm, ix, &lender, &[])
+    };
+
+    assert!(fill(&mut svm, 1500).is_err(), "Filling above the request's max rate should fail");
+    let lender_lamports_before = svm.get_balance(&lender.pubkey()).unwrap();
+    fill(&mut svm, 1000).expect("Fill borrow request should succeed");
+
+    // The request pre-funded the loan and vault rent, the lender only pays the transaction fee
+    assert!(lender_lamports_before - svm.get_balance(&lender.pubkey()).unwrap() < 10_000);
+
+    // Borrower receives the amount minus the 1% borrower fee
+    let borrower_fee = terms.loan_amount * 100 / 10000;
+    assert_eq!(get_token_balance(&svm, &lender_loan_account), 0);
+    assert_eq!(get_token_balance(&svm, &borrower_loan_account), terms.loan_amount - borrower_fee);
+    assert_eq!(get_token_balance(&svm, &pair.fee_recipient_loan_account), borrower_fee);
+
+    // Loan uses the same collateral vault scheme as take_loan
+    let (loan_pda, _) = get_pda_loan(&borrow_request, &borrower.pubkey());
+    let loan: Loan = get_account(&svm, &loan_pda);
+    assert_eq!(loan.lender.to_bytes(), lender.pubkey().to_bytes());
+    assert_eq!(loan.principal_amount, terms.loan_amount);
+    assert_eq!(loan.interest_rate_bps, 1000);
+    assert_eq!(loan.notice_duration, terms.notice_duration);
+    assert!(loan.is_active);
+    let (collateral_vault, _) = get_pda_collateral_vault(&loan_pda);
+    assert_eq!(get_token_balance(&svm, &collateral_vault), terms.collateral_amount);
+    assert!(svm.get_account(&borrow_request).is_none_or(|account| account.data.is_empty()));
+
+    let asset_pair_market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+    assert_eq!(asset_pair_market.total_borrowed, terms.loan_amount);
+
+    // Repaid like any other loan
+    mint_tokens(&mut svm, &pair.loan_mint, &borrower_loan_account, &admin, borrower_fee + 1_000_000);
+    let ix = repay_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &loan_pda,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        &lender.pubkey(),
+        &lender_loan_account,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Repay should succeed");
+    assert_eq!(get_token_balance(&svm, &borrower_collateral_account), terms.collateral_amount);
+
+    println!(" Borrow request filled by a lender and repaid");
+}
+
+#[test]
+fn test_orderbook() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    // Three 100 USDC offers: 10% at 80% LTV, 5% at 60% LTV, 5% at 80% LTV
+    let loan_amount = 100_000_000;
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, 3 * loan_amount);
+    for (offer_id, interest_rate_bps, ltv_bps) in [(1, 1000, 8000), (2, 500, 6000), (3, 500, 8000)] {
+        let terms = OfferTerms { offer_id, loan_amount, interest_rate_bps, ltv_bps, ..Default::default() };
+        let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &terms);
+        send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    }
+    let offers: Vec<Pubkey> = (1..=3).map(|offer_id| get_pda_lending_offer(&lender.pubkey(), offer_id).0).collect();
+
+    // Cheapest rate first, then the highest LTV
+    let orderbook = get_orderbook(&svm, &pair.orderbook);
+    assert_eq!(orderbook.asset_pair_market.to_bytes(), pair.asset_pair_market.to_bytes());
+    let book: Vec<[u8; 32]> = orderbook.entries().iter().map(|entry| entry.lending_offer.to_bytes()).collect();
+    assert_eq!(book, vec![offers[2].to_bytes(), offers[1].to_bytes(), offers[0].to_bytes()]);
+
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, 10_000_000_000);
+
+    // A partial fill updates the remaining amount, a full fill removes the offer
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &offers[2],
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        30_000_000,
+        2_000_000_000,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &offers[1],
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        loan_amount,
+        5_000_000_000,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+
+    let orderbook = get_orderbook(&svm, &pair.orderbook);
+    assert_eq!(orderbook.entries().len(), 2);
+    assert_eq!(orderbook.entries()[0].lending_offer.to_bytes(), offers[2].to_bytes());
+    assert_eq!(orderbook.entries()[0].remaining_amount, 70_000_000);
+    assert_eq!(orderbook.entries()[1].lending_offer.to_bytes(), offers[0].to_bytes());
+
+    // Cancelled offers leave the book
+    let ix = cancel_lending_offer_ix(&lender.pubkey(), &pair, &offers[0], &lender_loan_account);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Cancel lending offer should succeed");
+
+    let orderbook = get_orderbook(&svm, &pair.orderbook);
+    assert_eq!(orderbook.entries().len(), 1);
+    assert_eq!(orderbook.entries()[0].lending_offer.to_bytes(), offers[2].to_bytes());
+
+    println!(" Orderbook kept sorted through create, take and cancel");
+}
+
+#[test]
+fn test_index_lending_offer() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let cranker = Keypair::new();
+
+    for signer in [&admin, &lender, &cranker] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    // Fill the book with 5% offers, a 10% offer no longer fits
+    let loan_amount = 1_000_000;
+    let book_size = MAX_ORDERBOOK_ENTRIES as u64;
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, (book_size + 1) * loan_amount);
+    for offer_id in 1..=book_size + 1 {
+        let interest_rate_bps = if offer_id <= book_size { 500 } else { 1000 };
+        let terms = OfferTerms { offer_id, loan_amount, interest_rate_bps, ..Default::default() };
+        let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &terms);
+        send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    }
+    let (first_offer, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+    let (dropped_offer, _) = get_pda_lending_offer(&lender.pubkey(), book_size + 1);
+
+    let orderbook = get_orderbook(&svm, &pair.orderbook);
+    assert_eq!(orderbook.entries().len(), MAX_ORDERBOOK_ENTRIES);
+    assert!(!orderbook.entries().iter().any(|entry| entry.lending_offer.to_bytes() == dropped_offer.to_bytes()));
+
+    // Nothing changes while the book is full of better offers
+    let ix = index_lending_offer_ix(&cranker.pubkey(), &pair, &dropped_offer);
+    send_ix(&mut svm, ix, &cranker, &[]).expect("Index lending offer should succeed");
+    assert_eq!(get_orderbook(&svm, &pair.orderbook).entries().len(), MAX_ORDERBOOK_ENTRIES);
+
+    // Once a slot frees up anyone can put it back
+    let ix = cancel_lending_offer_ix(&lender.pubkey(), &pair, &first_offer, &lender_loan_account);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Cancel lending offer should succeed");
+    let ix = index_lending_offer_ix(&cranker.pubkey(), &pair, &dropped_offer);
+    send_ix(&mut svm, ix, &cranker, &[]).expect("Index lending offer should succeed");
+
+    let orderbook = get_orderbook(&svm, &pair.orderbook);
+    assert_eq!(orderbook.entries().len(), MAX_ORDERBOOK_ENTRIES);
+    let last = orderbook.entries().last().unwrap();
+    assert_eq!(last.lending_offer.to_bytes(), dropped_offer.to_bytes());
+    assert_eq!(last.interest_rate_bps, 1000);
+
+    // Indexing again is a no-op, cancelled offers can't be indexed
+    let ix = index_lending_offer_ix(&cranker.pubkey(), &pair, &dropped_offer);
+    send_ix(&mut svm, ix, &cranker, &[]).expect("Index lending offer should succeed");
+    assert_eq!(get_orderbook(&svm, &pair.orderbook).entries().len(), MAX_ORDERBOOK_ENTRIES);
+    let ix = index_lending_offer_ix(&cranker.pubkey(), &pair, &first_offer);
+    assert!(send_ix(&mut svm, ix, &cranker, &[]).is_err(), "Indexing a closed offer should fail");
+
+    println!(" Evicted offer re-indexed once space freed up");
+}
+
+#[test]
+fn test_borrow_market() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    // 100 USDC at 5% and 80% LTV, 100 USDC at 8% and 40% LTV, 500 USDC at 15% and 80% LTV
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, 700_000_000);
+    for (offer_id, loan_amount, interest_rate_bps, ltv_bps) in
+        [(1, 100_000_000, 500, 8000), (2, 100_000_000, 800, 4000), (3, 500_000_000, 1500, 8000)]
+    {
+        let terms = OfferTerms { offer_id, loan_amount, interest_rate_bps, ltv_bps, ..Default::default() };
+        let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &terms);
+        send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    }
+    let offers: Vec<Pubkey> = (1..=3).map(|offer_id| get_pda_lending_offer(&lender.pubkey(), offer_id).0).collect();
+
+    let collateral_amount = 3_000_000_000;
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, collateral_amount);
+
+    let borrow = |svm: &mut LiteSVM, amount: u64, max_interest_rate_bps: u64| {
+        let ix = borrow_market_ix(
+            &borrower.pubkey(),
+            &pair,
+            &offers,
+            &borrower_loan_account,
+            &borrower_collateral_account,
+            amount,
+            max_interest_rate_bps,
+            collateral_amount,
+        );
+        send_ix(svm, ix, &borrower, &[])
+    };
+
+    // Only 200 USDC is offered at or below 10%
+    assert!(borrow(&mut svm, 250_000_000, 1000).is_err(), "Unfillable market borrow should fail");
+    let offer: LendingOffer = get_account(&svm, &offers[0]);
+    assert_eq!(offer.remaining_amount, 100_000_000);
+
+    // Lamports sent to a loan PDA ahead of time must not block the fill
+    let (second_loan_pda, _) = get_pda_loan(&offers[1], &borrower.pubkey());
+    svm.airdrop(&second_loan_pda, 1).unwrap();
+
+    borrow(&mut svm, 150_000_000, 1000).expect("Market borrow should succeed");
+
+    // Borrower receives 150 USDC minus the 1% borrower fee; each loan gets the collateral its LTV requires
+    // (1.25 SOL for 100 USDC at 80% and for 50 USDC at 40%), the last one also takes the excess
+    assert_eq!(get_token_balance(&svm, &borrower_loan_account), 150_000_000 * 99 / 100);
+    assert_eq!(get_token_balance(&svm, &borrower_collateral_account), 0);
+
+    let (first_loan_pda, _) = get_pda_loan(&offers[0], &borrower.pubkey());
+    let first_loan: Loan = get_account(&svm, &first_loan_pda);
+    assert_eq!(first_loan.principal_amount, 100_000_000);
+    assert_eq!(first_loan.interest_rate_bps, 500);
+    assert_eq!(first_loan.collateral_amount, 1_250_000_000);
+
+    let second_loan: Loan = get_account(&svm, &second_loan_pda);
+    assert_eq!(second_loan.principal_amount, 50_000_000);
+    assert_eq!(second_loan.interest_rate_bps, 800);
+    assert_eq!(second_loan.collateral_amount, 1_750_000_000);
+    let (collateral_vault, _) = get_pda_collateral_vault(&second_loan_pda);
+    assert_eq!(get_token_balance(&svm, &collateral_vault), 1_750_000_000);
+
+    // The 15% offer was never touched
+    let (untouched_loan_pda, _) = get_pda_loan(&offers[2], &borrower.pubkey());
+    assert!(svm.get_account(&untouched_loan_pda).is_none_or(|account| account.data.is_empty()));
+
+    let orderbook = get_orderbook(&svm, &pair.orderbook);
+    assert_eq!(orderbook.entries().len(), 2);
+    assert_eq!(orderbook.entries()[0].lending_offer.to_bytes(), offers[1].to_bytes());
+    assert_eq!(orderbook.entries()[0].remaining_amount, 50_000_000);
+
+    let asset_pair_market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+    assert_eq!(asset_pair_market.total_borrowed, 150_000_000);
+    assert_eq!(asset_pair_market.total_available, 550_000_000);
+
+    println!(" Market borrow filled across the two cheapest offers");
+}
+
+#[test]
+fn test_offer_expiration() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+    let cranker = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower, &cranker] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    let now = svm.get_sysvar::<solana_sdk::clock::Clock>().unix_timestamp;
+    let loan_amount = 1_000_000_000;
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, 2 * loan_amount);
+
+    let terms = OfferTerms { expires_at: Some(now), ..Default::default() };
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &terms);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Offer expiring in the past should be rejected");
+
+    // Offer valid for one day
+    let terms = OfferTerms { expires_at: Some(now + 86400), ..Default::default() };
+    let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &terms);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    let (lending_offer, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+
+    let close = |svm: &mut LiteSVM| {
+        let ix = close_expired_offer_ix(&cranker.pubkey(), &pair, &lending_offer, &lender.pubkey(), &lender_loan_account);
+        send_ix(svm, ix, &cranker, &[])
+    };
+    assert!(close(&mut svm).is_err(), "Open offer should not be closable before it expires");
+
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, 10_000_000_000);
+
+    // Past expiry the offer can't be taken any more
+    warp_clock(&mut svm, 86400);
+    set_mock_price(&mut svm, &admin, &pair.loan_mint, USDC_PRICE);
+    set_mock_price(&mut svm, &admin, &pair.collateral_mint, SOL_PRICE);
+
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &lending_offer,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        500_000_000,
+        10_000_000_000,
+    );
+    assert!(send_ix(&mut svm, ix, &borrower, &[]).is_err(), "Expired offer should not be taken");
+
+    // Anyone can close it, escrow and rent go back to the lender
+    let lender_lamports_before = svm.get_account(&lender.pubkey()).unwrap().lamports;
+    close(&mut svm).expect("Closing an expired offer should succeed");
+
+    assert_eq!(get_token_balance(&svm, &lender_loan_account), 2 * loan_amount);
+    assert!(svm.get_account(&lender.pubkey()).unwrap().lamports > lender_lamports_before);
+    assert!(svm.get_account(&lending_offer).is_none_or(|account| account.data.is_empty()));
+
+    let asset_pair_market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+    assert_eq!(asset_pair_market.total_available, 0);
+    assert_eq!(get_orderbook(&svm, &pair.orderbook).entries().len(), 0);
+
+    println!(" Expired offer closed by a cranker");
+}
+
+#[test]
+fn test_update_and_resize_lending_offer() {
+    // Create the test environment
+    let mut svm = LiteSVM::new();
+
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let program_bytes = include_bytes!("../../../target/deploy/lending_market.so");
+    svm.add_program(program_id, program_bytes).unwrap();
+
+    let admin = Keypair::new();
+    let lender = Keypair::new();
+    let borrower = Keypair::new();
+
+    for signer in [&admin, &lender, &borrower] {
+        svm.airdrop(&signer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    }
+
+    let pair = setup_asset_pair_market(&mut svm, &admin);
+
+    // Two offers: 1000 USDC at 10% and 1000 USDC at 8%
+    let loan_amount = 1_000_000_000;
+    let lender_loan_account = create_token_account(&mut svm, &pair.loan_mint, &lender.pubkey());
+    mint_tokens(&mut svm, &pair.loan_mint, &lender_loan_account, &admin, 3 * loan_amount);
+    for (offer_id, interest_rate_bps) in [(1, 1000), (2, 800)] {
+        let terms = OfferTerms { offer_id, interest_rate_bps, ..Default::default() };
+        let ix = create_lending_offer_ix(&lender.pubkey(), &pair, &lender_loan_account, &terms);
+        send_ix(&mut svm, ix, &lender, &[]).expect("Lending offer creation should succeed");
+    }
+    let (lending_offer, _) = get_pda_lending_offer(&lender.pubkey(), 1);
+    let (other_offer, _) = get_pda_lending_offer(&lender.pubkey(), 2);
+
+    let update = OfferUpdate { ltv_bps: Some(13000), ..Default::default() };
+    let ix = update_lending_offer_ix(&lender.pubkey(), &pair, &lending_offer, &update);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "LTV above the liquidation LTV should be rejected");
+
+    // Reprice in place to 5% APR and 70% LTV, the offer moves to the top of the book
+    let update = OfferUpdate { interest_rate_bps: Some(500), ltv_bps: Some(7000), ..Default::default() };
+    let ix = update_lending_offer_ix(&lender.pubkey(), &pair, &lending_offer, &update);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Update lending offer should succeed");
+
+    let offer: LendingOffer = get_account(&svm, &lending_offer);
+    assert_eq!(offer.interest_rate_bps, 500);
+    assert_eq!(offer.ltv_bps, 7000);
+    assert_eq!(offer.notice_duration, 48 * 60 * 60);
+    let orderbook = get_orderbook(&svm, &pair.orderbook);
+    assert_eq!(orderbook.entries()[0].lending_offer.to_bytes(), lending_offer.to_bytes());
+    assert_eq!(orderbook.entries()[1].lending_offer.to_bytes(), other_offer.to_bytes());
+
+    // Resize the escrow: +500 then -300 USDC
+    let ix = top_up_offer_ix(&lender.pubkey(), &pair, &lending_offer, &lender_loan_account, 500_000_000);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Top up should succeed");
+    let ix = withdraw_from_offer_ix(&lender.pubkey(), &pair, &lending_offer, &lender_loan_account, 300_000_000);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Withdraw from offer should succeed");
+
+    let offer: LendingOffer = get_account(&svm, &lending_offer);
+    assert_eq!(offer.loan_amount, 1_200_000_000);
+    assert_eq!(offer.remaining_amount, 1_200_000_000);
+    let (escrow, _) = get_pda_escrow(&lending_offer);
+    assert_eq!(get_token_balance(&svm, &escrow), 1_200_000_000);
+    assert_eq!(get_token_balance(&svm, &lender_loan_account), 800_000_000);
+    assert_eq!(get_orderbook(&svm, &pair.orderbook).entries()[0].remaining_amount, 1_200_000_000);
+    let asset_pair_market: AssetPairMarket = get_account(&svm, &pair.asset_pair_market);
+    assert_eq!(asset_pair_market.total_available, 2_200_000_000);
+
+    let ix = withdraw_from_offer_ix(&lender.pubkey(), &pair, &lending_offer, &lender_loan_account, 1_200_000_000);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Withdrawing everything should go through cancel_lending_offer");
+
+    // Once taken, the terms are locked but the escrow can still be resized
+    let borrower_loan_account = create_token_account(&mut svm, &pair.loan_mint, &borrower.pubkey());
+    let borrower_collateral_account = create_token_account(&mut svm, &pair.collateral_mint, &borrower.pubkey());
+    mint_tokens(&mut svm, &pair.collateral_mint, &borrower_collateral_account, &admin, 10_000_000_000);
+    let ix = take_loan_ix(
+        &borrower.pubkey(),
+        &pair,
+        &lending_offer,
+        &borrower_loan_account,
+        &borrower_collateral_account,
+        200_000_000,
+        10_000_000_000,
+    );
+    send_ix(&mut svm, ix, &borrower, &[]).expect("Take loan should succeed");
+
+    let update = OfferUpdate { interest_rate_bps: Some(1500), ..Default::default() };
+    let ix = update_lending_offer_ix(&lender.pubkey(), &pair, &lending_offer, &update);
+    assert!(send_ix(&mut svm, ix, &lender, &[]).is_err(), "Taken offer terms should be locked");
+
+    let ix = withdraw_from_offer_ix(&lender.pubkey(), &pair, &lending_offer, &lender_loan_account, 500_000_000);
+    send_ix(&mut svm, ix, &lender, &[]).expect("Withdraw from a taken offer should succeed");
+    let offer: LendingOffer = get_account(&svm, &lending_offer);
+    assert_eq!(offer.remaining_amount, 500_000_000);
+    assert_eq!(offer.loan_amount - offer.remaining_amount, 200_000_000);
+
+    println!(" Lending offer repriced and resized in place");
+}
@@ -0,0 +1,65 @@
+use lending_market::math::{div_wad_up, exp_wad, mul_wad, mul_wad_up, pow_wad, WAD};
+
+// |actual - expected| <= expected * tolerance / WAD
+fn assert_close(actual: u128, expected: u128, tolerance: u128) {
+    let diff = actual.abs_diff(expected);
+    assert!(
+        diff <= mul_wad(expected, tolerance).unwrap(),
+        "{actual} not within tolerance of {expected}"
+    );
+}
+
+#[test]
+fn test_mul_wad_rounding() {
+    assert_eq!(mul_wad(3 * WAD, WAD / 2).unwrap(), 3 * WAD / 2);
+    assert_eq!(mul_wad(1, WAD / 2).unwrap(), 0);
+    assert_eq!(mul_wad_up(1, WAD / 2).unwrap(), 1);
+
+    // Large operands whose product overflows u128 but whose result does not
+    let big = u64::MAX as u128 * 1000;
+    assert_eq!(mul_wad(big, 1000 * WAD).unwrap(), big * 1000);
+    assert!(mul_wad(u128::MAX, 2 * WAD).is_err());
+}
+
+#[test]
+fn test_div_wad_up() {
+    assert_eq!(div_wad_up(3 * WAD, 2 * WAD).unwrap(), 3 * WAD / 2);
+    assert_eq!(div_wad_up(WAD, 3 * WAD).unwrap(), WAD / 3 + 1);
+    assert_eq!(div_wad_up(7 * WAD, 7 * WAD).unwrap(), WAD);
+    assert!(div_wad_up(WAD, 0).is_err());
+
+    // Growth factors far above 1 keep their ratio
+    let big = 1000 * WAD;
+    assert_close(div_wad_up(3 * big, big).unwrap(), 3 * WAD, 1);
+}
+
+#[test]
+fn test_pow_wad() {
+    assert_eq!(pow_wad(2 * WAD, 10).unwrap(), 1024 * WAD);
+    assert_eq!(pow_wad(7 * WAD, 0).unwrap(), WAD);
+
+    // Daily compounding at 10% APR for a year: (1 + 0.1/365)^365 = 1.105155781616...
+    let daily = WAD + WAD / 10 / 365;
+    assert_close(pow_wad(daily, 365).unwrap(), 1_105_155_781_616_264_095, 1_000_000);
+}
+
+#[test]
+fn test_exp_wad() {
+    assert_eq!(exp_wad(0).unwrap(), WAD);
+    assert_close(exp_wad(WAD).unwrap(), 2_718_281_828_459_045_235, 1_000);
+    assert_close(exp_wad(WAD / 10).unwrap(), 1_105_170_918_075_647_624, 1_000);
+    assert_close(exp_wad(5 * WAD / 2).unwrap(), 12_182_493_960_703_473_438, 1_000);
+}
+
+#[test]
+fn test_multi_year_growth_stays_in_range() {
+    // 100% APR (the maximum offer rate) compounded daily for 30 years, and continuously for 40 years
+    let daily = WAD + WAD / 365;
+    assert_close(pow_wad(daily, 365 * 30).unwrap(), 10_256_974_372_144 * WAD, 1_000_000_000);
+
+    let growth = exp_wad(40 * WAD).unwrap();
+    assert_close(growth, 235_385_266_837_019_985 * WAD, 1_000_000_000);
+
+    // Interest on a u64 principal at that growth still fits the math
+    assert!(mul_wad_up(u64::MAX as u128, growth - WAD).is_ok());
+}
@@ -1,5 +1,9 @@
+use anchor_lang::AccountDeserialize;
+use lending_market::instructions::RateModel;
+use lending_market::state::{InterestMode, Orderbook};
 use litesvm::LiteSVM;
 use solana_sdk::{
+    clock::Clock,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     signature::{Keypair, Signer},
@@ -10,7 +14,10 @@ use solana_sdk::{
 const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
 const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::new_from_array([0u8; 32]);
 
-use lending_market;
+// Mock oracle prices (Pyth-style, 8 decimal exponent)
+pub const PRICE_EXPO: i32 = -8;
+pub const USDC_PRICE: i64 = 100_000_000; // $1
+pub const SOL_PRICE: i64 = 10_000_000_000; // $100
 
 // Test Utils
 // create Anchor instruction discriminator
@@ -150,6 +157,1160 @@ pub fn get_token_balance(svm: &LiteSVM, token_account: &Pubkey) -> u64 {
     u64::from_le_bytes(account_data[64..72].try_into().unwrap())
 }
 
+// Helper to create a program-owned mock price feed for a mint (admin only)
+pub fn create_mock_price_feed(
+    svm: &mut LiteSVM,
+    admin: &Keypair,
+    mint: &Pubkey,
+    price: i64,
+    expo: i32,
+) -> Pubkey {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (lending_market_pda, _) = get_pda_lending_market();
+    let (mock_price_feed_pda, _) = get_pda_mock_price_feed(mint);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "init_mock_price_feed"));
+    data.extend_from_slice(&price.to_le_bytes());
+    data.extend_from_slice(&expo.to_le_bytes());
+
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(admin.pubkey(), true),
+            AccountMeta::new_readonly(lending_market_pda, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(mock_price_feed_pda, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&admin.pubkey()),
+        &[admin],
+        svm.latest_blockhash(),
+    );
+
+    svm.send_transaction(tx).unwrap();
+    mock_price_feed_pda
+}
+
+// Helper to publish a new mock price for a mint (admin only)
+pub fn set_mock_price(svm: &mut LiteSVM, admin: &Keypair, mint: &Pubkey, price: i64) {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (lending_market_pda, _) = get_pda_lending_market();
+    let (mock_price_feed_pda, _) = get_pda_mock_price_feed(mint);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "set_mock_price"));
+    data.extend_from_slice(&price.to_le_bytes());
+
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(admin.pubkey(), true),
+            AccountMeta::new_readonly(lending_market_pda, false),
+            AccountMeta::new(mock_price_feed_pda, false),
+        ],
+        data,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&admin.pubkey()),
+        &[admin],
+        svm.latest_blockhash(),
+    );
+
+    svm.send_transaction(tx).unwrap();
+}
+
+// Helper to read and deserialize a program account (Loan, LendingMarket, ...)
+// move the clock forward, mock prices need refreshing afterwards to stay fresh
+pub fn warp_clock(svm: &mut LiteSVM, seconds: i64) {
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp += seconds;
+    svm.set_sysvar(&clock);
+}
+
+pub fn get_account<T: AccountDeserialize>(svm: &LiteSVM, address: &Pubkey) -> T {
+    let account = svm.get_account(address).unwrap();
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+// Helper to send a single instruction signed by `payer` (plus extra signers)
+// Errors carry the transaction error and program logs
+// Zero-copy accounts are read with bytemuck instead of AccountDeserialize
+pub fn get_orderbook(svm: &LiteSVM, address: &Pubkey) -> Orderbook {
+    let account = svm.get_account(address).unwrap();
+    bytemuck::pod_read_unaligned(&account.data[8..8 + std::mem::size_of::<Orderbook>()])
+}
+
+pub fn send_ix(
+    svm: &mut LiteSVM,
+    ix: Instruction,
+    payer: &Keypair,
+    signers: &[&Keypair],
+) -> Result<(), String> {
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &all_signers,
+        svm.latest_blockhash(),
+    );
+    let result = svm
+        .send_transaction(tx)
+        .map(|_| ())
+        .map_err(|e| format!("{:?}\n{}", e.err, e.meta.logs.join("\n")));
+    // Allow an identical instruction to be sent again in the next transaction
+    svm.expire_blockhash();
+    result
+}
+
+// Helper to initialize the lending market
+pub fn init_lending_market(
+    svm: &mut LiteSVM,
+    admin: &Keypair,
+    fee_recipient: &Pubkey,
+    lender_fee_bps: u64,
+    borrower_fee_bps: u64,
+) -> Pubkey {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (lending_market_pda, _) = get_pda_lending_market();
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "init_lending_market"));
+    data.extend_from_slice(&fee_recipient.to_bytes());
+    data.extend_from_slice(&lender_fee_bps.to_le_bytes());
+    data.extend_from_slice(&borrower_fee_bps.to_le_bytes());
+
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(admin.pubkey(), true),
+            AccountMeta::new(lending_market_pda, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    };
+
+    send_ix(svm, ix, admin, &[]).unwrap();
+    lending_market_pda
+}
+
+// Accounts shared by every instruction on one asset pair
+pub struct PairAccounts {
+    pub lending_market: Pubkey,
+    pub asset_pair_market: Pubkey,
+    pub orderbook: Pubkey,
+    pub loan_mint: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub loan_price_feed: Pubkey,
+    pub collateral_price_feed: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub fee_recipient_loan_account: Pubkey,
+}
+
+// Helper to initialize the market (2% lender fee, 1% borrower fee) and a
+// USDC-like (6 decimals, $1) / SOL-like (9 decimals, $100) asset pair
+pub fn setup_asset_pair_market(svm: &mut LiteSVM, admin: &Keypair) -> PairAccounts {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let fee_recipient = Keypair::new().pubkey();
+    let lending_market = init_lending_market(svm, admin, &fee_recipient, 200, 100);
+
+    let loan_mint = create_mint(svm, &admin.pubkey(), 6);
+    let collateral_mint = create_mint(svm, &admin.pubkey(), 9);
+    let fee_recipient_loan_account = create_token_account(svm, &loan_mint, &fee_recipient);
+
+    let loan_price_feed = create_mock_price_feed(svm, admin, &loan_mint, USDC_PRICE, PRICE_EXPO);
+    let collateral_price_feed = create_mock_price_feed(svm, admin, &collateral_mint, SOL_PRICE, PRICE_EXPO);
+
+    let (asset_pair_market, _) = get_pda_asset_pair_market(&loan_mint, &collateral_mint);
+    let (orderbook, _) = get_pda_orderbook(&asset_pair_market);
+
+    // Mock feeds are identified by their mint
+    let mut data = anchor_discriminator("global", "create_asset_pair_market").to_vec();
+    data.extend_from_slice(&loan_mint.to_bytes());
+    data.extend_from_slice(&collateral_mint.to_bytes());
+
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(admin.pubkey(), true),
+            AccountMeta::new_readonly(lending_market, false),
+            AccountMeta::new(asset_pair_market, false),
+            AccountMeta::new(orderbook, false),
+            AccountMeta::new_readonly(loan_mint, false),
+            AccountMeta::new_readonly(collateral_mint, false),
+            AccountMeta::new_readonly(loan_price_feed, false),
+            AccountMeta::new_readonly(collateral_price_feed, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    };
+    send_ix(svm, ix, admin, &[]).unwrap();
+
+    PairAccounts {
+        lending_market,
+        asset_pair_market,
+        orderbook,
+        loan_mint,
+        collateral_mint,
+        loan_price_feed,
+        collateral_price_feed,
+        fee_recipient,
+        fee_recipient_loan_account,
+    }
+}
+
+// Terms passed to create_lending_offer
+pub struct OfferTerms {
+    pub offer_id: u64,
+    pub loan_amount: u64,
+    pub interest_rate_bps: u64,
+    pub ltv_bps: u64,
+    pub min_fill_amount: u64,
+    pub interest_mode: InterestMode,
+    pub penalty_rate_bps: u64,
+    pub notice_duration: i64,
+    pub duration: Option<i64>,
+    pub expires_at: Option<i64>,
+}
+
+impl Default for OfferTerms {
+    // 1000 USDC at 10% APR simple interest and 80% LTV, no minimum fill, no penalty rate, 48h notice, perpetual, no expiry
+    fn default() -> Self {
+        Self {
+            offer_id: 1,
+            loan_amount: 1_000_000_000,
+            interest_rate_bps: 1000,
+            ltv_bps: 8000,
+            min_fill_amount: 0,
+            interest_mode: InterestMode::Simple,
+            penalty_rate_bps: 0,
+            notice_duration: 48 * 60 * 60,
+            duration: None,
+            expires_at: None,
+        }
+    }
+}
+
+// Terms passed to create_borrow_request
+pub struct RequestTerms {
+    pub request_id: u64,
+    pub loan_amount: u64,
+    pub collateral_amount: u64,
+    pub max_interest_rate_bps: u64,
+    pub ltv_bps: u64,
+    pub notice_duration: i64,
+}
+
+impl Default for RequestTerms {
+    // 500 USDC against 10 SOL at up to 12% APR and 80% LTV, 48h notice
+    fn default() -> Self {
+        Self {
+            request_id: 1,
+            loan_amount: 500_000_000,
+            collateral_amount: 10_000_000_000,
+            max_interest_rate_bps: 1200,
+            ltv_bps: 8000,
+            notice_duration: 48 * 60 * 60,
+        }
+    }
+}
+
+// Instruction builders
+pub fn create_lending_offer_ix(
+    lender: &Pubkey,
+    pair: &PairAccounts,
+    lender_token_account: &Pubkey,
+    terms: &OfferTerms,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (lending_offer, _) = get_pda_lending_offer(lender, terms.offer_id);
+    let (escrow, _) = get_pda_escrow(&lending_offer);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "create_lending_offer"));
+    data.extend_from_slice(&terms.offer_id.to_le_bytes());
+    data.extend_from_slice(&terms.loan_amount.to_le_bytes());
+    data.extend_from_slice(&terms.interest_rate_bps.to_le_bytes());
+    data.extend_from_slice(&terms.ltv_bps.to_le_bytes());
+    data.extend_from_slice(&terms.min_fill_amount.to_le_bytes());
+    data.push(terms.interest_mode as u8);
+    data.extend_from_slice(&terms.penalty_rate_bps.to_le_bytes());
+    data.extend_from_slice(&terms.notice_duration.to_le_bytes());
+    match terms.duration {
+        Some(duration) => {
+            data.push(1);
+            data.extend_from_slice(&duration.to_le_bytes());
+        }
+        None => data.push(0),
+    }
+    match terms.expires_at {
+        Some(expires_at) => {
+            data.push(1);
+            data.extend_from_slice(&expires_at.to_le_bytes());
+        }
+        None => data.push(0),
+    }
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(*lender, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+            AccountMeta::new(pair.orderbook, false),
+            AccountMeta::new(lending_offer, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(pair.loan_mint, false),
+            AccountMeta::new(*lender_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+// Terms passed to update_lending_offer, `None` keeps the current value
+#[derive(Default)]
+pub struct OfferUpdate {
+    pub interest_rate_bps: Option<u64>,
+    pub ltv_bps: Option<u64>,
+    pub min_fill_amount: Option<u64>,
+    pub penalty_rate_bps: Option<u64>,
+    pub notice_duration: Option<i64>,
+}
+
+pub fn update_lending_offer_ix(
+    lender: &Pubkey,
+    pair: &PairAccounts,
+    lending_offer: &Pubkey,
+    update: &OfferUpdate,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "update_lending_offer"));
+    for value in [update.interest_rate_bps, update.ltv_bps, update.min_fill_amount, update.penalty_rate_bps] {
+        match value {
+            Some(value) => {
+                data.push(1);
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            None => data.push(0),
+        }
+    }
+    match update.notice_duration {
+        Some(notice_duration) => {
+            data.push(1);
+            data.extend_from_slice(&notice_duration.to_le_bytes());
+        }
+        None => data.push(0),
+    }
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*lender, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(*lending_offer, false),
+            AccountMeta::new_readonly(pair.asset_pair_market, false),
+            AccountMeta::new(pair.orderbook, false),
+        ],
+        data,
+    }
+}
+
+pub fn top_up_offer_ix(
+    lender: &Pubkey,
+    pair: &PairAccounts,
+    lending_offer: &Pubkey,
+    lender_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (escrow, _) = get_pda_escrow(lending_offer);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "top_up_offer"));
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*lender, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(*lending_offer, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+            AccountMeta::new(pair.orderbook, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new(*lender_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data,
+    }
+}
+
+pub fn withdraw_from_offer_ix(
+    lender: &Pubkey,
+    pair: &PairAccounts,
+    lending_offer: &Pubkey,
+    lender_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (escrow, _) = get_pda_escrow(lending_offer);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "withdraw_from_offer"));
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*lender, true),
+            AccountMeta::new(*lending_offer, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+            AccountMeta::new(pair.orderbook, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new(*lender_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data,
+    }
+}
+
+pub fn close_expired_offer_ix(
+    cranker: &Pubkey,
+    pair: &PairAccounts,
+    lending_offer: &Pubkey,
+    lender: &Pubkey,
+    lender_token_account: &Pubkey,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (escrow, _) = get_pda_escrow(lending_offer);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*cranker, true),
+            AccountMeta::new(*lender, false),
+            AccountMeta::new(*lending_offer, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+            AccountMeta::new(pair.orderbook, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new(*lender_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: anchor_discriminator("global", "close_expired_offer").to_vec(),
+    }
+}
+
+pub fn index_lending_offer_ix(cranker: &Pubkey, pair: &PairAccounts, lending_offer: &Pubkey) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*cranker, true),
+            AccountMeta::new_readonly(*lending_offer, false),
+            AccountMeta::new_readonly(pair.asset_pair_market, false),
+            AccountMeta::new(pair.orderbook, false),
+        ],
+        data: anchor_discriminator("global", "index_lending_offer").to_vec(),
+    }
+}
+
+pub fn take_loan_ix(
+    borrower: &Pubkey,
+    pair: &PairAccounts,
+    lending_offer: &Pubkey,
+    borrower_loan_account: &Pubkey,
+    borrower_collateral_account: &Pubkey,
+    borrow_amount: u64,
+    collateral_amount: u64,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (loan, _) = get_pda_loan(lending_offer, borrower);
+    let (collateral_vault, _) = get_pda_collateral_vault(&loan);
+    let (escrow, _) = get_pda_escrow(lending_offer);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "take_loan"));
+    data.extend_from_slice(&borrow_amount.to_le_bytes());
+    data.extend_from_slice(&collateral_amount.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(*borrower, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+            AccountMeta::new(pair.orderbook, false),
+            AccountMeta::new(*lending_offer, false),
+            AccountMeta::new(loan, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new(collateral_vault, false),
+            AccountMeta::new_readonly(pair.loan_mint, false),
+            AccountMeta::new_readonly(pair.collateral_mint, false),
+            AccountMeta::new_readonly(pair.loan_price_feed, false),
+            AccountMeta::new_readonly(pair.collateral_price_feed, false),
+            AccountMeta::new(*borrower_loan_account, false),
+            AccountMeta::new(*borrower_collateral_account, false),
+            AccountMeta::new(pair.fee_recipient, false),
+            AccountMeta::new(pair.fee_recipient_loan_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+// Remaining accounts are the offer, escrow, loan and collateral vault of each offer, in the order given
+#[allow(clippy::too_many_arguments)]
+pub fn borrow_market_ix(
+    borrower: &Pubkey,
+    pair: &PairAccounts,
+    lending_offers: &[Pubkey],
+    borrower_loan_account: &Pubkey,
+    borrower_collateral_account: &Pubkey,
+    amount: u64,
+    max_interest_rate_bps: u64,
+    collateral_amount: u64,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "borrow_market"));
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&max_interest_rate_bps.to_le_bytes());
+    data.extend_from_slice(&collateral_amount.to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new(*borrower, true),
+        AccountMeta::new_readonly(pair.lending_market, false),
+        AccountMeta::new(pair.asset_pair_market, false),
+        AccountMeta::new(pair.orderbook, false),
+        AccountMeta::new_readonly(pair.collateral_mint, false),
+        AccountMeta::new_readonly(pair.loan_price_feed, false),
+        AccountMeta::new_readonly(pair.collateral_price_feed, false),
+        AccountMeta::new(*borrower_loan_account, false),
+        AccountMeta::new(*borrower_collateral_account, false),
+        AccountMeta::new(pair.fee_recipient, false),
+        AccountMeta::new(pair.fee_recipient_loan_account, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    for lending_offer in lending_offers {
+        let (escrow, _) = get_pda_escrow(lending_offer);
+        let (loan, _) = get_pda_loan(lending_offer, borrower);
+        let (collateral_vault, _) = get_pda_collateral_vault(&loan);
+        accounts.push(AccountMeta::new(*lending_offer, false));
+        accounts.push(AccountMeta::new(escrow, false));
+        accounts.push(AccountMeta::new(loan, false));
+        accounts.push(AccountMeta::new(collateral_vault, false));
+    }
+
+    Instruction { program_id, accounts, data }
+}
+
+pub fn cancel_lending_offer_ix(
+    lender: &Pubkey,
+    pair: &PairAccounts,
+    lending_offer: &Pubkey,
+    lender_token_account: &Pubkey,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (escrow, _) = get_pda_escrow(lending_offer);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(*lender, true),
+            AccountMeta::new(*lending_offer, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+            AccountMeta::new(pair.orderbook, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new(*lender_token_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: anchor_discriminator("global", "cancel_lending_offer").to_vec(),
+    }
+}
+
+pub fn repay_loan_ix(
+    borrower: &Pubkey,
+    pair: &PairAccounts,
+    loan: &Pubkey,
+    borrower_loan_account: &Pubkey,
+    borrower_collateral_account: &Pubkey,
+    lender: &Pubkey,
+    lender_loan_account: &Pubkey,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (collateral_vault, _) = get_pda_collateral_vault(loan);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(*borrower, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(*loan, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+            AccountMeta::new(collateral_vault, false),
+            AccountMeta::new(*borrower_loan_account, false),
+            AccountMeta::new(*borrower_collateral_account, false),
+            AccountMeta::new(*lender, false),
+            AccountMeta::new(*lender_loan_account, false),
+            AccountMeta::new(pair.fee_recipient, false),
+            AccountMeta::new(pair.fee_recipient_loan_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: anchor_discriminator("global", "repay_loan").to_vec(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn partial_repay_ix(
+    borrower: &Pubkey,
+    pair: &PairAccounts,
+    loan: &Pubkey,
+    borrower_loan_account: &Pubkey,
+    borrower_collateral_account: &Pubkey,
+    lender: &Pubkey,
+    lender_loan_account: &Pubkey,
+    amount: u64,
+    release_collateral: bool,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (collateral_vault, _) = get_pda_collateral_vault(loan);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "partial_repay"));
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(release_collateral as u8);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(*borrower, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(*loan, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+            AccountMeta::new_readonly(pair.loan_price_feed, false),
+            AccountMeta::new_readonly(pair.collateral_price_feed, false),
+            AccountMeta::new(collateral_vault, false),
+            AccountMeta::new(*borrower_loan_account, false),
+            AccountMeta::new(*borrower_collateral_account, false),
+            AccountMeta::new_readonly(*lender, false),
+            AccountMeta::new(*lender_loan_account, false),
+            AccountMeta::new_readonly(pair.fee_recipient, false),
+            AccountMeta::new(pair.fee_recipient_loan_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn refinance_loan_ix(
+    borrower: &Pubkey,
+    pair: &PairAccounts,
+    old_loan: &Pubkey,
+    old_lender: &Pubkey,
+    old_lender_loan_account: &Pubkey,
+    lending_offer: &Pubkey,
+    borrower_loan_account: &Pubkey,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (old_collateral_vault, _) = get_pda_collateral_vault(old_loan);
+    let (escrow, _) = get_pda_escrow(lending_offer);
+    let (new_loan, _) = get_pda_loan(lending_offer, borrower);
+    let (new_collateral_vault, _) = get_pda_collateral_vault(&new_loan);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(*borrower, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+            AccountMeta::new(pair.orderbook, false),
+            AccountMeta::new(*old_loan, false),
+            AccountMeta::new(old_collateral_vault, false),
+            AccountMeta::new_readonly(*old_lender, false),
+            AccountMeta::new(*old_lender_loan_account, false),
+            AccountMeta::new(*lending_offer, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new(new_loan, false),
+            AccountMeta::new(new_collateral_vault, false),
+            AccountMeta::new_readonly(pair.collateral_mint, false),
+            AccountMeta::new_readonly(pair.loan_price_feed, false),
+            AccountMeta::new_readonly(pair.collateral_price_feed, false),
+            AccountMeta::new(*borrower_loan_account, false),
+            AccountMeta::new(pair.fee_recipient, false),
+            AccountMeta::new(pair.fee_recipient_loan_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: anchor_discriminator("global", "refinance_loan").to_vec(),
+    }
+}
+
+pub fn create_borrow_request_ix(
+    borrower: &Pubkey,
+    pair: &PairAccounts,
+    borrower_collateral_account: &Pubkey,
+    terms: &RequestTerms,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (borrow_request, _) = get_pda_borrow_request(borrower, terms.request_id);
+    let (collateral_escrow, _) = get_pda_request_collateral(&borrow_request);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "create_borrow_request"));
+    data.extend_from_slice(&terms.request_id.to_le_bytes());
+    data.extend_from_slice(&terms.loan_amount.to_le_bytes());
+    data.extend_from_slice(&terms.collateral_amount.to_le_bytes());
+    data.extend_from_slice(&terms.max_interest_rate_bps.to_le_bytes());
+    data.extend_from_slice(&terms.ltv_bps.to_le_bytes());
+    data.extend_from_slice(&terms.notice_duration.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(*borrower, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new_readonly(pair.asset_pair_market, false),
+            AccountMeta::new(borrow_request, false),
+            AccountMeta::new(collateral_escrow, false),
+            AccountMeta::new_readonly(pair.collateral_mint, false),
+            AccountMeta::new(*borrower_collateral_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+pub fn cancel_borrow_request_ix(
+    borrower: &Pubkey,
+    borrow_request: &Pubkey,
+    borrower_collateral_account: &Pubkey,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (collateral_escrow, _) = get_pda_request_collateral(borrow_request);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(*borrower, true),
+            AccountMeta::new(*borrow_request, false),
+            AccountMeta::new(collateral_escrow, false),
+            AccountMeta::new(*borrower_collateral_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: anchor_discriminator("global", "cancel_borrow_request").to_vec(),
+    }
+}
+
+pub fn fill_borrow_request_ix(
+    lender: &Pubkey,
+    pair: &PairAccounts,
+    borrow_request: &Pubkey,
+    borrower: &Pubkey,
+    lender_token_account: &Pubkey,
+    borrower_loan_account: &Pubkey,
+    interest_rate_bps: u64,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (request_collateral_escrow, _) = get_pda_request_collateral(borrow_request);
+    let (loan, _) = get_pda_loan(borrow_request, borrower);
+    let (collateral_vault, _) = get_pda_collateral_vault(&loan);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "fill_borrow_request"));
+    data.extend_from_slice(&interest_rate_bps.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(*lender, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+            AccountMeta::new(*borrow_request, false),
+            AccountMeta::new(request_collateral_escrow, false),
+            AccountMeta::new(*borrower, false),
+            AccountMeta::new(loan, false),
+            AccountMeta::new(collateral_vault, false),
+            AccountMeta::new_readonly(pair.loan_mint, false),
+            AccountMeta::new_readonly(pair.collateral_mint, false),
+            AccountMeta::new_readonly(pair.loan_price_feed, false),
+            AccountMeta::new_readonly(pair.collateral_price_feed, false),
+            AccountMeta::new(*lender_token_account, false),
+            AccountMeta::new(*borrower_loan_account, false),
+            AccountMeta::new(pair.fee_recipient, false),
+            AccountMeta::new(pair.fee_recipient_loan_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+pub fn deposit_collateral_ix(
+    borrower: &Pubkey,
+    loan: &Pubkey,
+    borrower_collateral_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (collateral_vault, _) = get_pda_collateral_vault(loan);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "deposit_collateral"));
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*borrower, true),
+            AccountMeta::new(*loan, false),
+            AccountMeta::new(collateral_vault, false),
+            AccountMeta::new(*borrower_collateral_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data,
+    }
+}
+
+pub fn withdraw_collateral_ix(
+    borrower: &Pubkey,
+    pair: &PairAccounts,
+    loan: &Pubkey,
+    borrower_collateral_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (collateral_vault, _) = get_pda_collateral_vault(loan);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "withdraw_collateral"));
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*borrower, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(*loan, false),
+            AccountMeta::new_readonly(pair.asset_pair_market, false),
+            AccountMeta::new_readonly(pair.loan_price_feed, false),
+            AccountMeta::new_readonly(pair.collateral_price_feed, false),
+            AccountMeta::new(collateral_vault, false),
+            AccountMeta::new(*borrower_collateral_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data,
+    }
+}
+
+pub fn request_repayment_ix(lender: &Pubkey, pair: &PairAccounts, loan: &Pubkey) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*lender, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(*loan, false),
+        ],
+        data: anchor_discriminator("global", "request_repayment").to_vec(),
+    }
+}
+
+pub fn cancel_repayment_request_ix(lender: &Pubkey, pair: &PairAccounts, loan: &Pubkey) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*lender, true),
+            AccountMeta::new(*loan, false),
+            AccountMeta::new_readonly(pair.asset_pair_market, false),
+        ],
+        data: anchor_discriminator("global", "cancel_repayment_request").to_vec(),
+    }
+}
+
+pub fn extend_repayment_deadline_ix(
+    lender: &Pubkey,
+    pair: &PairAccounts,
+    loan: &Pubkey,
+    new_repayment_deadline: i64,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "extend_repayment_deadline"));
+    data.extend_from_slice(&new_repayment_deadline.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*lender, true),
+            AccountMeta::new(*loan, false),
+            AccountMeta::new_readonly(pair.asset_pair_market, false),
+        ],
+        data,
+    }
+}
+
+pub fn liquidate_loan_ix(
+    lender: &Pubkey,
+    pair: &PairAccounts,
+    loan: &Pubkey,
+    lender_collateral_account: &Pubkey,
+    borrower_collateral_account: &Pubkey,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (collateral_vault, _) = get_pda_collateral_vault(loan);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(*lender, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(*loan, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+            AccountMeta::new_readonly(pair.loan_price_feed, false),
+            AccountMeta::new_readonly(pair.collateral_price_feed, false),
+            AccountMeta::new(collateral_vault, false),
+            AccountMeta::new(*lender_collateral_account, false),
+            AccountMeta::new(*borrower_collateral_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: anchor_discriminator("global", "liquidate_loan").to_vec(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn keeper_liquidate_loan_ix(
+    keeper: &Pubkey,
+    pair: &PairAccounts,
+    loan: &Pubkey,
+    keeper_loan_account: &Pubkey,
+    keeper_collateral_account: &Pubkey,
+    lender: &Pubkey,
+    lender_loan_account: &Pubkey,
+    borrower: &Pubkey,
+    borrower_collateral_account: &Pubkey,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (collateral_vault, _) = get_pda_collateral_vault(loan);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*keeper, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(*loan, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+            AccountMeta::new_readonly(pair.loan_price_feed, false),
+            AccountMeta::new_readonly(pair.collateral_price_feed, false),
+            AccountMeta::new(collateral_vault, false),
+            AccountMeta::new(*keeper_loan_account, false),
+            AccountMeta::new(*keeper_collateral_account, false),
+            AccountMeta::new_readonly(*lender, false),
+            AccountMeta::new(*lender_loan_account, false),
+            AccountMeta::new(*borrower, false),
+            AccountMeta::new(*borrower_collateral_account, false),
+            AccountMeta::new_readonly(pair.fee_recipient, false),
+            AccountMeta::new(pair.fee_recipient_loan_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: anchor_discriminator("global", "keeper_liquidate_loan").to_vec(),
+    }
+}
+
+pub fn start_collateral_auction_ix(lender: &Pubkey, pair: &PairAccounts, loan: &Pubkey) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (collateral_vault, _) = get_pda_collateral_vault(loan);
+    let (collateral_auction, _) = get_pda_collateral_auction(loan);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(*lender, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(*loan, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+            AccountMeta::new_readonly(pair.loan_price_feed, false),
+            AccountMeta::new_readonly(pair.collateral_price_feed, false),
+            AccountMeta::new_readonly(collateral_vault, false),
+            AccountMeta::new(collateral_auction, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: anchor_discriminator("global", "start_collateral_auction").to_vec(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn bid_collateral_auction_ix(
+    bidder: &Pubkey,
+    pair: &PairAccounts,
+    loan: &Pubkey,
+    bidder_loan_account: &Pubkey,
+    bidder_collateral_account: &Pubkey,
+    lender: &Pubkey,
+    lender_loan_account: &Pubkey,
+    borrower: &Pubkey,
+    borrower_loan_account: &Pubkey,
+    max_price: u64,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (collateral_vault, _) = get_pda_collateral_vault(loan);
+    let (collateral_auction, _) = get_pda_collateral_auction(loan);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "bid_collateral_auction"));
+    data.extend_from_slice(&max_price.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*bidder, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(collateral_auction, false),
+            AccountMeta::new(*loan, false),
+            AccountMeta::new_readonly(pair.asset_pair_market, false),
+            AccountMeta::new(collateral_vault, false),
+            AccountMeta::new(*bidder_loan_account, false),
+            AccountMeta::new(*bidder_collateral_account, false),
+            AccountMeta::new(*lender, false),
+            AccountMeta::new(*lender_loan_account, false),
+            AccountMeta::new_readonly(*borrower, false),
+            AccountMeta::new(*borrower_loan_account, false),
+            AccountMeta::new_readonly(pair.fee_recipient, false),
+            AccountMeta::new(pair.fee_recipient_loan_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data,
+    }
+}
+
+pub fn settle_collateral_auction_ix(
+    lender: &Pubkey,
+    pair: &PairAccounts,
+    loan: &Pubkey,
+    lender_collateral_account: &Pubkey,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (collateral_vault, _) = get_pda_collateral_vault(loan);
+    let (collateral_auction, _) = get_pda_collateral_auction(loan);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(*lender, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(collateral_auction, false),
+            AccountMeta::new(*loan, false),
+            AccountMeta::new(collateral_vault, false),
+            AccountMeta::new(*lender_collateral_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: anchor_discriminator("global", "settle_collateral_auction").to_vec(),
+    }
+}
+
+pub fn set_protocol_paused_ix(authority: &Pubkey, is_paused: bool) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    let (lending_market_pda, _) = get_pda_lending_market();
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "set_protocol_paused"));
+    data.push(is_paused as u8);
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(lending_market_pda, false),
+        ],
+        data,
+    }
+}
+
+// Admin instruction that only needs the admin, the lending market and the asset pair
+pub fn asset_pair_admin_ix(name: &str, admin: &Pubkey, pair: &PairAccounts) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+        ],
+        data: anchor_discriminator("global", name).to_vec(),
+    }
+}
+
+// update_asset_pair_market arguments, `None` keeps the current value
+#[derive(Default)]
+pub struct PairRiskParams {
+    pub liquidation_ltv_bps: Option<u64>,
+    pub liquidation_penalty_bps: Option<u64>,
+    pub liquidation_bonus_bps: Option<u64>,
+    pub rate_model: Option<RateModel>,
+}
+
+pub fn update_asset_pair_market_ix(
+    admin: &Pubkey,
+    pair: &PairAccounts,
+    params: &PairRiskParams,
+) -> Instruction {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&anchor_discriminator("global", "update_asset_pair_market"));
+    for value in [params.liquidation_ltv_bps, params.liquidation_penalty_bps, params.liquidation_bonus_bps] {
+        match value {
+            Some(bps) => {
+                data.push(1);
+                data.extend_from_slice(&bps.to_le_bytes());
+            }
+            None => data.push(0),
+        }
+    }
+    match params.rate_model {
+        Some(model) => {
+            data.push(1);
+            for bps in [model.base_rate_bps, model.slope_bps, model.jump_slope_bps, model.kink_bps] {
+                data.extend_from_slice(&bps.to_le_bytes());
+            }
+        }
+        None => data.push(0),
+    }
+
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(pair.lending_market, false),
+            AccountMeta::new(pair.asset_pair_market, false),
+        ],
+        data,
+    }
+}
+
 // PDA derivation functions
 pub fn get_pda_lending_market() -> (Pubkey, u8) {
     let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
@@ -164,6 +1325,11 @@ pub fn get_pda_asset_pair_market(loan_mint: &Pubkey, collateral_mint: &Pubkey) -
     )
 }
 
+pub fn get_pda_orderbook(asset_pair_market: &Pubkey) -> (Pubkey, u8) {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    Pubkey::find_program_address(&[b"orderbook", asset_pair_market.as_ref()], &program_id)
+}
+
 pub fn get_pda_lending_offer(lender: &Pubkey, offer_id: u64) -> (Pubkey, u8) {
     let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
     Pubkey::find_program_address(
@@ -189,3 +1355,26 @@ pub fn get_pda_collateral_vault(loan: &Pubkey) -> (Pubkey, u8) {
     let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
     Pubkey::find_program_address(&[b"collateral", loan.as_ref()], &program_id)
 }
+
+pub fn get_pda_borrow_request(borrower: &Pubkey, request_id: u64) -> (Pubkey, u8) {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    Pubkey::find_program_address(
+        &[b"borrow_request", borrower.as_ref(), &request_id.to_le_bytes()],
+        &program_id,
+    )
+}
+
+pub fn get_pda_request_collateral(borrow_request: &Pubkey) -> (Pubkey, u8) {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    Pubkey::find_program_address(&[b"request_collateral", borrow_request.as_ref()], &program_id)
+}
+
+pub fn get_pda_mock_price_feed(mint: &Pubkey) -> (Pubkey, u8) {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    Pubkey::find_program_address(&[b"mock_price", mint.as_ref()], &program_id)
+}
+
+pub fn get_pda_collateral_auction(loan: &Pubkey) -> (Pubkey, u8) {
+    let program_id = Pubkey::new_from_array(lending_market::ID.to_bytes());
+    Pubkey::find_program_address(&[b"auction", loan.as_ref()], &program_id)
+}